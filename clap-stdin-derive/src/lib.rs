@@ -0,0 +1,193 @@
+//! Derive macro for the `FromSource` trait of [clap-stdin](https://docs.rs/clap-stdin)
+//!
+//! Use it through the `derive` feature of `clap-stdin`, which re-exports it as
+//! `clap_stdin::FromSource`.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitChar, LitStr};
+
+/// Derive `clap_stdin::FromSource`
+///
+/// For a struct with a single field, the value is parsed with `FromStr` from `stdin` and from the
+/// arg value, as configured with `#[from_source(...)]`:
+/// - `stdin = "contents"` (the default): the entire contents of `stdin`
+/// - `stdin = "lines"`: each non-blank line is an item of the field's collection type
+/// - `arg = "value"` (the default): the entire arg value
+/// - `arg = "split(',')"`: each item between the delimiters is an item of the collection
+///
+/// With `#[from_source(serde = "json")]` (or `"yaml"`, `"toml"`), the whole type is deserialized
+/// from `stdin` or from the arg value instead, which requires the matching feature of `clap-stdin`.
+#[proc_macro_derive(FromSource, attributes(from_source))]
+pub fn derive_from_source(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How the value is read from `stdin`
+enum StdinMode {
+    Contents,
+    Lines,
+}
+
+/// How the value is read from the arg value
+enum ArgMode {
+    Value,
+    Split(LitChar),
+}
+
+/// The options of the `#[from_source(...)]` attributes
+#[derive(Default)]
+struct Attrs {
+    stdin: Option<LitStr>,
+    arg: Option<LitStr>,
+    serde: Option<LitStr>,
+}
+
+impl Attrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = Attrs::default();
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("from_source"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let slot = if meta.path.is_ident("stdin") {
+                    &mut attrs.stdin
+                } else if meta.path.is_ident("arg") {
+                    &mut attrs.arg
+                } else if meta.path.is_ident("serde") {
+                    &mut attrs.serde
+                } else {
+                    return Err(meta.error("expected `stdin`, `arg` or `serde`"));
+                };
+                *slot = Some(meta.value()?.parse()?);
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+
+    fn stdin_mode(&self) -> syn::Result<StdinMode> {
+        match &self.stdin {
+            None => Ok(StdinMode::Contents),
+            Some(mode) => match mode.value().as_str() {
+                "contents" => Ok(StdinMode::Contents),
+                "lines" => Ok(StdinMode::Lines),
+                _ => Err(syn::Error::new(
+                    mode.span(),
+                    "expected `\"contents\"` or `\"lines\"`",
+                )),
+            },
+        }
+    }
+
+    fn arg_mode(&self) -> syn::Result<ArgMode> {
+        let Some(mode) = &self.arg else {
+            return Ok(ArgMode::Value);
+        };
+        let value = mode.value();
+        if value == "value" {
+            return Ok(ArgMode::Value);
+        }
+        value
+            .strip_prefix("split(")
+            .and_then(|v| v.strip_suffix(')'))
+            .and_then(|delimiter| syn::parse_str::<LitChar>(delimiter).ok())
+            .map(|delimiter| ArgMode::Split(LitChar::new(delimiter.value(), mode.span())))
+            .ok_or_else(|| syn::Error::new(mode.span(), "expected `\"value\"` or `\"split(',')\"`"))
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = Attrs::parse(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = match &attrs.serde {
+        Some(format) => expand_serde(format, &attrs)?,
+        None => expand_field(input, &attrs)?,
+    };
+    Ok(quote! {
+        impl #impl_generics ::clap_stdin::FromSource for #name #ty_generics #where_clause {
+            type Err = ::clap_stdin::StdinError;
+
+            fn from_source(source: ::clap_stdin::Source) -> ::std::result::Result<Self, Self::Err> {
+                #body
+            }
+        }
+    })
+}
+
+/// Deserialize the whole type from `stdin` or from the arg value
+fn expand_serde(format: &LitStr, attrs: &Attrs) -> syn::Result<TokenStream2> {
+    if let Some(mode) = attrs.stdin.as_ref().or(attrs.arg.as_ref()) {
+        return Err(syn::Error::new(
+            mode.span(),
+            "`stdin` and `arg` can't be combined with `serde`",
+        ));
+    }
+    let deserialize = match format.value().as_str() {
+        "json" => quote!(::clap_stdin::derive::json),
+        "yaml" => quote!(::clap_stdin::derive::yaml),
+        "toml" => quote!(::clap_stdin::derive::toml),
+        _ => {
+            return Err(syn::Error::new(
+                format.span(),
+                "expected `\"json\"`, `\"yaml\"` or `\"toml\"`",
+            ))
+        }
+    };
+    Ok(quote! {
+        match source {
+            ::clap_stdin::Source::Stdin(stdin) => #deserialize(&stdin.read_string()?),
+            ::clap_stdin::Source::SharedStdin(stdin) => #deserialize(&stdin.read_string()?),
+            ::clap_stdin::Source::Arg(arg) => #deserialize(&arg),
+            source => #deserialize(&::std::string::String::try_from(source)?),
+        }
+    })
+}
+
+/// Parse the single field of a struct from `stdin` or from the arg value
+fn expand_field(input: &DeriveInput, attrs: &Attrs) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "FromSource can only be derived for structs with a single field, or with `serde`",
+        ));
+    };
+    let construct =
+        match &data.fields {
+            Fields::Named(fields) if fields.named.len() == 1 => {
+                let field = &fields.named[0].ident;
+                quote!(Self { #field: value })
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote!(Self(value)),
+            _ => return Err(syn::Error::new_spanned(
+                &input.ident,
+                "FromSource can only be derived for structs with a single field, or with `serde`",
+            )),
+        };
+    let from_stdin = match attrs.stdin_mode()? {
+        StdinMode::Contents => quote!(::clap_stdin::derive::value(&stdin.read_string()?)),
+        StdinMode::Lines => quote!(::clap_stdin::derive::lines(stdin.lines_numbered()?)),
+    };
+    let from_arg = match attrs.arg_mode()? {
+        ArgMode::Value => quote!(::clap_stdin::derive::value(&arg)),
+        ArgMode::Split(delimiter) => quote!(::clap_stdin::derive::split(&arg, #delimiter)),
+    };
+    Ok(quote! {
+        let value = match source {
+            ::clap_stdin::Source::Stdin(stdin) => #from_stdin,
+            ::clap_stdin::Source::SharedStdin(stdin) => #from_stdin,
+            ::clap_stdin::Source::Arg(arg) => #from_arg,
+            source => {
+                let arg = ::std::string::String::try_from(source)?;
+                #from_arg
+            }
+        }?;
+        ::std::result::Result::Ok(#construct)
+    })
+}
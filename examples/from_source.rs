@@ -42,10 +42,12 @@ impl FromSource for Fruits {
                     .collect::<Result<Vec<String>, _>>()?;
                 Ok(Fruits(fruits))
             }
+            Source::SharedStdin(stdin) => Ok(Fruits(stdin.lines()?.collect::<Result<_, _>>()?)),
             Source::Arg(arg) => {
                 let fruits = arg.split(",").map(String::from).collect::<Vec<_>>();
                 Ok(Fruits(fruits))
             }
+            source @ (Source::Path(_) | Source::Fd(_)) => Ok(Fruits(vec![source.try_into()?])),
         }
     }
 }
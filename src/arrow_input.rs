@@ -0,0 +1,246 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::str::FromStr;
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use super::{read_to_end_limited, Source, SourceKind, StdinError, StdinOpts};
+
+/// Iterator over the record batches of an [`ArrowInput`]
+pub type RecordBatches = Box<dyn Iterator<Item = Result<RecordBatch, StdinError>> + Send>;
+
+/// Wrapper struct to read columnar data from a file or `stdin` as Arrow [`RecordBatch`]es
+///
+/// Files can be Parquet, Arrow IPC files or Arrow IPC streams (detected from their contents),
+/// while `stdin` (for `-`) is read as an Arrow IPC stream.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::ArrowInput;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: ArrowInput,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let mut rows = 0;
+/// for batch in args.input.into_batches()? {
+///     rows += batch?.num_rows();
+/// }
+/// println!("rows={rows}");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ ./example data.parquet
+/// $ cat data.arrows | ./example -
+/// ```
+#[derive(Clone)]
+pub struct ArrowInput {
+    source: Source,
+    opts: StdinOpts,
+}
+
+impl ArrowInput {
+    /// A `value_parser` that reads the data as configured by `opts`
+    ///
+    /// Besides the sentinel, [`StdinOpts::max_bytes`] limits the size of a file or of `stdin`
+    /// (which is then buffered, to check its size before decoding it), and [`StdinOpts::timeout`]
+    /// and [`StdinOpts::prompt`] apply when `stdin` is read. Text options (E.g. trimming) don't
+    /// apply to columnar data.
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| {
+            Ok(Self {
+                source: opts.source(s),
+                opts: opts.clone().with_env_overrides()?,
+            })
+        }
+    }
+
+    /// Where the data is read from: [`SourceKind::Stdin`], [`SourceKind::Fd`] or
+    /// [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        match &self.source {
+            Source::Stdin(_) | Source::SharedStdin(_) => SourceKind::Stdin,
+            Source::Fd(fd) => SourceKind::Fd(*fd),
+            Source::Arg(path) => SourceKind::File(path.into()),
+            Source::Path(path) => SourceKind::File(path.clone()),
+        }
+    }
+
+    /// Is the data read from stdin
+    pub fn is_stdin(&self) -> bool {
+        self.source.is_stdin()
+    }
+
+    /// Open the source, returning an iterator that reads the record batches one at a time
+    pub fn into_batches(self) -> Result<RecordBatches, StdinError> {
+        match self.source {
+            source @ (Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_)) => {
+                let mut reader =
+                    source.reader_with(std::any::type_name::<Self>(), Some(&self.opts))?;
+                if let Some(max_bytes) = self.opts.max_bytes {
+                    let contents = read_to_end_limited(reader, Some(max_bytes))?;
+                    reader = Box::new(io::Cursor::new(contents));
+                }
+                Ok(Box::new(
+                    StreamReader::try_new(reader, None)?.map(|batch| Ok(batch?)),
+                ))
+            }
+            source => {
+                let path = source.as_path().expect("the source is a path");
+                let mut file = File::open(path).map_err(StdinError::file(path, "open"))?;
+                if let Some(max_bytes) = self.opts.max_bytes {
+                    if file.metadata()?.len() > max_bytes {
+                        return Err(StdinError::InputTooLarge(max_bytes));
+                    }
+                }
+                let mut magic = [0; 6];
+                let len = read_prefix(&mut file, &mut magic)?;
+                file.seek(SeekFrom::Start(0))?;
+                let batches: RecordBatches = match &magic[..len] {
+                    [b'P', b'A', b'R', b'1', ..] => Box::new(
+                        ParquetRecordBatchReaderBuilder::try_new(file)?
+                            .build()?
+                            .map(|batch| Ok(batch?)),
+                    ),
+                    b"ARROW1" => Box::new(FileReader::try_new(file, None)?.map(|batch| Ok(batch?))),
+                    _ => Box::new(
+                        StreamReader::try_new(io::BufReader::new(file), None)?
+                            .map(|batch| Ok(batch?)),
+                    ),
+                };
+                Ok(batches)
+            }
+        }
+    }
+
+    /// Read all record batches
+    pub fn read_all(self) -> Result<Vec<RecordBatch>, StdinError> {
+        self.into_batches()?.collect()
+    }
+}
+
+/// Read up to `buf.len()` bytes, stopping early at the end of the input
+fn read_prefix(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
+
+impl FromStr for ArrowInput {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArrowInput::with_opts(StdinOpts::default())(s)
+    }
+}
+
+impl std::fmt::Debug for ArrowInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArrowInput")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl From<arrow_schema::ArrowError> for StdinError {
+    fn from(e: arrow_schema::ArrowError) -> Self {
+        StdinError::Arrow(e.to_string())
+    }
+}
+
+impl From<parquet::errors::ParquetError> for StdinError {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        StdinError::Arrow(e.to_string())
+    }
+}
+
+#[test]
+fn test_arrow_formats() {
+    use std::sync::Arc;
+
+    use arrow_array::Int32Array;
+
+    let batch = RecordBatch::try_from_iter([(
+        "id",
+        Arc::new(Int32Array::from(vec![1, 2, 3])) as arrow_array::ArrayRef,
+    )])
+    .unwrap();
+    let schema = batch.schema();
+
+    let read = |tmp: &tempfile::NamedTempFile| {
+        let input: ArrowInput = tmp.path().to_str().unwrap().parse().unwrap();
+        input.read_all().unwrap()
+    };
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let mut writer =
+        parquet::arrow::ArrowWriter::try_new(tmp.reopen().unwrap(), schema.clone(), None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    assert_eq!(read(&tmp), std::slice::from_ref(&batch));
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let mut writer =
+        arrow_ipc::writer::FileWriter::try_new(tmp.reopen().unwrap(), &schema).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(read(&tmp), std::slice::from_ref(&batch));
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let mut writer =
+        arrow_ipc::writer::StreamWriter::try_new(tmp.reopen().unwrap(), &schema).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(read(&tmp), std::slice::from_ref(&batch));
+
+    use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+    for compression in [
+        Compression::SNAPPY,
+        Compression::ZSTD(ZstdLevel::default()),
+        Compression::GZIP(GzipLevel::default()),
+    ] {
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_compression(compression)
+            .build();
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(
+            tmp.reopen().unwrap(),
+            schema.clone(),
+            Some(props),
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        assert_eq!(read(&tmp), std::slice::from_ref(&batch));
+    }
+
+    let parse = ArrowInput::with_opts(StdinOpts::new().max_bytes(8));
+    let input = parse(tmp.path().to_str().unwrap()).unwrap();
+    assert!(matches!(
+        input.into_batches(),
+        Err(StdinError::InputTooLarge(8))
+    ));
+    let _session = crate::StdinSession::with_input(std::fs::read(&tmp).unwrap());
+    assert!(matches!(
+        parse("-").unwrap().into_batches(),
+        Err(StdinError::InputTooLarge(8))
+    ));
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "not arrow").unwrap();
+    let input: ArrowInput = tmp.path().to_str().unwrap().parse().unwrap();
+    assert!(matches!(input.into_batches(), Err(StdinError::Arrow(_))));
+}
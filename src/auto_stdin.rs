@@ -0,0 +1,277 @@
+//! Optional positional arg falling back to `stdin`, see [`AutoStdin`]
+use std::ffi::OsStr;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use clap::error::ErrorKind;
+
+use super::value_parser::error_kind;
+use super::{MaybeStdin, SourceKind, StdinValueParser};
+
+/// Id and value name of the positional arg added by an [`AutoStdin`]
+///
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{AutoStdin, AutoStdinArg};
+///
+/// #[derive(Debug, Clone)]
+/// struct Greeting;
+///
+/// impl AutoStdinArg for Greeting {
+///     const ID: &'static str = "greeting";
+///     const VALUE_NAME: &'static str = "GREETING";
+/// }
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[command(flatten)]
+///     greeting: AutoStdin<String, Greeting>,
+///     #[arg(long, conflicts_with = "greeting")]
+///     default_greeting: bool,
+/// }
+/// ```
+pub trait AutoStdinArg {
+    /// Id of the arg, E.g. for `conflicts_with` or `mut_arg`
+    const ID: &'static str;
+    /// Value name of the arg, used in the help and in errors
+    const VALUE_NAME: &'static str;
+}
+
+/// The default [`AutoStdinArg`]: an `[INPUT]` arg with the id `auto_stdin`
+#[derive(Debug, Clone)]
+pub struct InputArg;
+
+impl AutoStdinArg for InputArg {
+    const ID: &'static str = "auto_stdin";
+    const VALUE_NAME: &'static str = "INPUT";
+}
+
+/// Optional positional arg that reads its value from `stdin` when it's omitted
+///
+/// Flattened into a command, `AutoStdin` adds an `[INPUT]` positional arg, without the
+/// `default_value = "-"` needed for [`MaybeStdin`], so `echo hi | ./example` and `./example hi`
+/// both just work. Passing `-` reads `stdin` too. Like `-`, an omitted arg fails with
+/// [`StdinError::StdinIsTty`](crate::StdinError::StdinIsTty) when `stdin` is a terminal.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::AutoStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[command(flatten)]
+///     greeting: AutoStdin<String>,
+/// }
+///
+/// let args = Args::parse();
+/// println!("{}", *args.greeting);
+/// ```
+///
+/// ```sh
+/// $ ./example hi
+/// $ echo hi | ./example
+/// ```
+///
+/// Only one `AutoStdin` can be flattened into a command, since only one arg can read `stdin`. The
+/// id and value name of the arg are set by an [`AutoStdinArg`], [`InputArg`] by default.
+#[derive(Debug, Clone)]
+pub struct AutoStdin<T, A = InputArg> {
+    inner: MaybeStdin<T>,
+    arg: PhantomData<A>,
+}
+
+impl<T, A> AutoStdin<T, A> {
+    /// Where this value originated from: [`SourceKind::Stdin`] or [`SourceKind::Literal`]
+    pub fn source_kind(&self) -> SourceKind {
+        self.inner.source_kind()
+    }
+
+    /// Was this value read from stdin (shorthand for checking [`AutoStdin::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.inner.is_stdin()
+    }
+
+    /// Extract the value from the wrapper
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+}
+
+impl<T, A> clap::FromArgMatches for AutoStdin<T, A>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+    A: AutoStdinArg,
+{
+    fn from_arg_matches(matches: &clap::ArgMatches) -> Result<Self, clap::Error> {
+        // The hidden default value makes the arg always present
+        let inner = matches
+            .get_one::<MaybeStdin<T>>(A::ID)
+            .cloned()
+            .ok_or_else(|| clap::Error::new(ErrorKind::MissingRequiredArgument))?;
+        Ok(Self {
+            inner,
+            arg: PhantomData,
+        })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &clap::ArgMatches) -> Result<(), clap::Error> {
+        if matches.value_source(A::ID) == Some(clap::parser::ValueSource::CommandLine) {
+            *self = Self::from_arg_matches(matches)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, A> clap::Args for AutoStdin<T, A>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+    A: AutoStdinArg,
+{
+    fn augment_args(cmd: clap::Command) -> clap::Command {
+        cmd.arg(
+            clap::Arg::new(A::ID)
+                .value_name(A::VALUE_NAME)
+                .help("Value to use, read from stdin when omitted")
+                .default_value("-")
+                .hide_default_value(true)
+                .value_parser(AutoStdinParser::<T>::new()),
+        )
+    }
+
+    fn augment_args_for_update(cmd: clap::Command) -> clap::Command {
+        Self::augment_args(cmd)
+    }
+}
+
+/// Value parser of an [`AutoStdin`] arg, naming the arg when `stdin` can't be read
+struct AutoStdinParser<T> {
+    literal: StdinValueParser<MaybeStdin<T>>,
+}
+
+impl<T> AutoStdinParser<T>
+where
+    T: FromStr + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    fn new() -> Self {
+        Self {
+            literal: StdinValueParser::new(MaybeStdin::<T>::from_str),
+        }
+    }
+}
+
+impl<T> Clone for AutoStdinParser<T> {
+    fn clone(&self) -> Self {
+        Self {
+            literal: self.literal.clone(),
+        }
+    }
+}
+
+impl<T> clap::builder::TypedValueParser for AutoStdinParser<T>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    type Value = MaybeStdin<T>;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        if value != "-" {
+            return self.literal.parse_ref(cmd, arg, value);
+        }
+        MaybeStdin::from_str("-").map_err(|e| {
+            let arg = arg.map_or_else(|| "...".to_owned(), |arg| arg.to_string());
+            clap::Error::raw(
+                error_kind(&e),
+                format!("unable to read {arg} from stdin: {e}\n"),
+            )
+            .with_cmd(cmd)
+        })
+    }
+}
+
+impl<T, A> std::ops::Deref for AutoStdin<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, A> std::ops::DerefMut for AutoStdin<T, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[test]
+fn test_auto_stdin() {
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct Args {
+        #[command(flatten)]
+        value: AutoStdin<u32>,
+    }
+
+    let args = Args::try_parse_from(["example", "42"]).unwrap();
+    assert_eq!(args.value.source_kind(), SourceKind::Literal);
+    assert_eq!(*args.value, 42);
+
+    let _session = crate::StdinSession::with_input("7\n");
+    let args = Args::try_parse_from(["example"]).unwrap();
+    assert!(args.value.is_stdin());
+    assert_eq!(args.value.into_inner(), 7);
+
+    let _session = crate::StdinSession::with_input("seven\n");
+    let error = Args::try_parse_from(["example"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    assert!(error
+        .to_string()
+        .contains("unable to read [INPUT] from stdin"));
+}
+
+#[test]
+fn test_auto_stdin_arg() {
+    use clap::Parser;
+
+    #[derive(Debug, Clone)]
+    struct Count;
+
+    impl AutoStdinArg for Count {
+        const ID: &'static str = "count";
+        const VALUE_NAME: &'static str = "COUNT";
+    }
+
+    #[derive(Debug, Parser)]
+    struct Args {
+        #[command(flatten)]
+        count: AutoStdin<u32, Count>,
+        #[arg(long, conflicts_with = "count")]
+        all: bool,
+    }
+
+    let args = Args::try_parse_from(["example", "3"]).unwrap();
+    assert_eq!(*args.count, 3);
+    assert!(!args.all);
+
+    let error = Args::try_parse_from(["example", "3", "--all"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+
+    let error = Args::try_parse_from(["example", "three"]).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("invalid value 'three' for '[COUNT]'"));
+
+    let _session = crate::StdinSession::with_input("three\n");
+    let error = Args::try_parse_from(["example"]).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("unable to read [COUNT] from stdin"));
+}
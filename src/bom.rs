@@ -0,0 +1,128 @@
+//! Stripping of a leading UTF-8 byte order mark from text input, see
+//! [`StdinOpts::keep_bom`](crate::StdinOpts::keep_bom)
+use std::io::{self, BufRead, Read};
+
+/// The UTF-8 encoding of `U+FEFF`, as written by Notepad and other Windows tools
+pub(crate) const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Remove a leading byte order mark from `value`
+pub(crate) fn strip_bom(mut value: String) -> String {
+    if value.starts_with('\u{feff}') {
+        value.drain(..BOM.len());
+    }
+    value
+}
+
+/// Reader skipping a leading byte order mark, checked on the first read
+pub(crate) struct SkipBom<R> {
+    inner: R,
+    checked: bool,
+}
+
+impl<R: BufRead> SkipBom<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self::unless_kept(inner, false)
+    }
+
+    /// Skip a leading byte order mark, unless `keep_bom` is set
+    pub(crate) fn unless_kept(inner: R, keep_bom: bool) -> Self {
+        Self {
+            inner,
+            checked: keep_bom,
+        }
+    }
+
+    fn skip(&mut self) -> io::Result<()> {
+        if !self.checked {
+            if self.inner.fill_buf()?.starts_with(BOM) {
+                self.inner.consume(BOM.len());
+            }
+            self.checked = true;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for SkipBom<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.skip()?;
+        self.inner.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for SkipBom<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.skip()?;
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+    }
+}
+
+#[test]
+fn test_skip_bom() {
+    let reader = SkipBom::new(io::Cursor::new(b"\xef\xbb\xbfvalue\xef\xbb\xbf"));
+    assert_eq!(io::read_to_string(reader).unwrap(), "value\u{feff}");
+    let reader = SkipBom::new(io::Cursor::new(b"value"));
+    assert_eq!(io::read_to_string(reader).unwrap(), "value");
+    assert_eq!(strip_bom("\u{feff}42".to_string()), "42");
+    assert_eq!(strip_bom("42\u{feff}".to_string()), "42\u{feff}");
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_bom_file() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "\u{feff}first\nsecond\n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+    let input: crate::FileOrStdin = path.parse().unwrap();
+    assert_eq!(input.lines().next().unwrap().unwrap(), "first");
+    let parse = crate::FileOrStdin::<String>::with_opts(crate::StdinOpts::new().keep_bom());
+    assert_eq!(
+        parse(path).unwrap().contents().unwrap(),
+        "\u{feff}first\nsecond"
+    );
+    assert_eq!(
+        parse(path).unwrap().lines().next().unwrap().unwrap(),
+        "\u{feff}first"
+    );
+
+    // Byte APIs return the input as is
+    let input: crate::FileOrStdin = path.parse().unwrap();
+    assert!(input.contents_bytes().unwrap().starts_with(BOM));
+    let mut input: crate::FileOrStdin = path.parse().unwrap();
+    assert!(input.fill_buf().unwrap().starts_with(BOM));
+}
+
+#[cfg(all(feature = "file", feature = "serde"))]
+#[test]
+fn test_bom_document() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "\u{feff}{\"a\": 1}").unwrap();
+    let input: crate::FileOrStdin = tmp.path().to_str().unwrap().parse().unwrap();
+    let value: serde_json::Value = input.deserialize_json().unwrap();
+    assert_eq!(value["a"], 1);
+}
+
+#[test]
+fn test_bom_source() {
+    let _session = crate::StdinSession::with_input("\u{feff}first\n");
+    let source: crate::Source = "-".parse().unwrap();
+    assert_eq!(source.lines().unwrap().next().unwrap().unwrap(), "first");
+
+    let _session = crate::StdinSession::with_input("\u{feff}raw");
+    let source: crate::Source = "-".parse().unwrap();
+    let mut contents = Vec::new();
+    source.reader().unwrap().read_to_end(&mut contents).unwrap();
+    assert!(contents.starts_with(BOM));
+}
+
+#[cfg(feature = "maybe-stdin")]
+#[test]
+fn test_bom_value() {
+    let _session = crate::StdinSession::with_input("\u{feff}42\n");
+    let value: crate::MaybeStdin<u32> = "-".parse().unwrap();
+    assert_eq!(*value, 42);
+}
@@ -0,0 +1,120 @@
+//! Shell completion hints for args read from a file or `stdin`, see [`StdinArgExt`]
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+
+/// Extension methods for [`clap::Arg`]s taking the wrapper types
+///
+/// A value parser can't tell clap how to complete the value, so file args need a
+/// [`ValueHint`](clap::ValueHint) for `clap_complete` to complete paths. With the derive API,
+/// set it with `#[arg(value_hint = clap::ValueHint::FilePath)]`. Static completions don't offer
+/// the `-` sentinel, use [`complete_file_or_stdin`] with dynamic completions for that.
+/// ```rust
+/// use clap::{Arg, Command, ValueHint};
+/// use clap_stdin::{FileOrStdin, StdinArgExt};
+///
+/// let command = Command::new("example").arg(
+///     Arg::new("input")
+///         .value_parser(clap::value_parser!(FileOrStdin))
+///         .file_or_stdin(),
+/// );
+/// let input = command.get_arguments().next().unwrap();
+/// assert_eq!(input.get_value_hint(), ValueHint::FilePath);
+/// ```
+pub trait StdinArgExt {
+    /// Complete the value as a file path, for [`FileOrStdin`](crate::FileOrStdin),
+    /// [`FileOrStdinVec`](crate::FileOrStdinVec), [`PathOrStdin`](crate::PathOrStdin) and
+    /// other args that name a file (or `-` for `stdin`)
+    fn file_or_stdin(self) -> Self;
+}
+
+impl StdinArgExt for clap::Arg {
+    fn file_or_stdin(self) -> Self {
+        self.value_hint(clap::ValueHint::FilePath)
+    }
+}
+
+/// Completion candidates for an arg naming a file or `-` for `stdin`: the literal `-`, followed by
+/// the paths starting with `current`
+///
+/// Value hints only let the shell complete paths, so `-` is never offered. With `clap_complete`'s
+/// dynamic completions, add this to the arg as an `ArgValueCompleter` instead:
+/// ```rust,ignore
+/// use clap_complete::engine::{ArgValueCompleter, CompletionCandidate};
+///
+/// Arg::new("input")
+///     .value_parser(clap::value_parser!(FileOrStdin))
+///     .add(ArgValueCompleter::new(|current: &std::ffi::OsStr| {
+///         clap_stdin::complete_file_or_stdin(current)
+///             .into_iter()
+///             .map(CompletionCandidate::new)
+///             .collect()
+///     }))
+/// ```
+///
+/// Directories end with a path separator, so their contents can be completed next.
+pub fn complete_file_or_stdin(current: &OsStr) -> Vec<OsString> {
+    let mut candidates = Vec::new();
+    if current.is_empty() || current == "-" {
+        candidates.push(OsString::from("-"));
+    }
+    let current = Path::new(current);
+    let typed = current.as_os_str().as_encoded_bytes();
+    let (dir, prefix) = match typed.last() {
+        Some(last) if std::path::is_separator(char::from(*last)) => (current, OsStr::new("")),
+        _ => (
+            current.parent().unwrap_or(Path::new("")),
+            current.file_name().unwrap_or_default(),
+        ),
+    };
+    let listed = match dir.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => dir,
+    };
+    let Ok(entries) = std::fs::read_dir(listed) else {
+        return candidates;
+    };
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_name()
+                .as_encoded_bytes()
+                .starts_with(prefix.as_encoded_bytes())
+        })
+        .map(|entry| {
+            let mut path = dir.join(entry.file_name()).into_os_string();
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                path.push(std::path::MAIN_SEPARATOR_STR);
+            }
+            path
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    candidates.extend(paths);
+    candidates
+}
+
+#[test]
+fn test_complete_file_or_stdin() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(tmp.path().join("input.txt"), "").unwrap();
+    std::fs::create_dir(tmp.path().join("inputs")).unwrap();
+    std::fs::write(tmp.path().join("other.txt"), "").unwrap();
+
+    let dir = format!("{}{}", tmp.path().display(), std::path::MAIN_SEPARATOR);
+    let candidates = complete_file_or_stdin(OsStr::new(&format!("{dir}inp")));
+    assert_eq!(
+        candidates,
+        [
+            OsString::from(format!("{dir}input.txt")),
+            OsString::from(format!("{dir}inputs{}", std::path::MAIN_SEPARATOR)),
+        ]
+    );
+
+    let candidates = complete_file_or_stdin(OsStr::new(&dir));
+    assert_eq!(candidates.len(), 3);
+    assert!(!candidates.contains(&OsString::from("-")));
+
+    assert_eq!(complete_file_or_stdin(OsStr::new("-"))[0], "-");
+    assert_eq!(complete_file_or_stdin(OsStr::new(""))[0], "-");
+}
@@ -0,0 +1,185 @@
+#[cfg(any(
+    feature = "maybe-stdin",
+    feature = "from-source",
+    feature = "vec",
+    feature = "file"
+))]
+use std::fmt::Display;
+use std::io;
+#[cfg(any(feature = "maybe-stdin", feature = "from-source", feature = "vec"))]
+use std::io::Cursor;
+
+#[cfg(feature = "maybe-stdin")]
+use super::MaybeStdin;
+#[cfg(feature = "from-source")]
+use super::MaybeStdinFromSource;
+#[cfg(feature = "vec")]
+use super::MaybeStdinVec;
+#[cfg(feature = "file")]
+use super::{FileOrStdin, PathOrStdin, Utf8Policy};
+use super::{SourceKind, StdinError};
+
+/// Common interface of the wrapper types, for code that accepts "anything that provides input"
+///
+/// ```rust
+/// # #[cfg(all(feature = "maybe-stdin", feature = "file"))]
+/// # {
+/// use clap_stdin::{ContentSource, FileOrStdin, MaybeStdin, SourceKind};
+///
+/// fn describe<S: ContentSource>(input: S) -> Result<String, clap_stdin::StdinError>
+/// where
+///     S::Value: std::fmt::Display,
+/// {
+///     let kind = input.source_kind();
+///     Ok(format!("{} (from {kind:?})", input.contents()?))
+/// }
+///
+/// let value: MaybeStdin<u32> = "42".parse().unwrap();
+/// assert_eq!(describe(value).unwrap(), "42 (from Literal)");
+/// # }
+/// ```
+pub trait ContentSource {
+    /// The parsed contents
+    type Value;
+    /// Reader over the raw contents
+    type Reader: io::Read;
+
+    /// The parsed contents, reading them from the source if needed
+    fn contents(self) -> Result<Self::Value, StdinError>;
+
+    /// A reader over the raw contents
+    ///
+    /// For types that parse their value up front, this reads the value's `Display` output
+    fn into_reader(self) -> Result<Self::Reader, StdinError>;
+
+    /// Where the contents originate from
+    fn source_kind(&self) -> SourceKind;
+}
+
+#[cfg(feature = "maybe-stdin")]
+impl<T: Display> ContentSource for MaybeStdin<T> {
+    type Value = T;
+    type Reader = Cursor<Vec<u8>>;
+
+    fn contents(self) -> Result<T, StdinError> {
+        Ok(self.into_inner())
+    }
+
+    fn into_reader(self) -> Result<Self::Reader, StdinError> {
+        Ok(Cursor::new(self.to_string().into_bytes()))
+    }
+
+    fn source_kind(&self) -> SourceKind {
+        MaybeStdin::source_kind(self)
+    }
+}
+
+#[cfg(feature = "from-source")]
+impl<T: Display> ContentSource for MaybeStdinFromSource<T> {
+    type Value = T;
+    type Reader = Cursor<Vec<u8>>;
+
+    fn contents(self) -> Result<T, StdinError> {
+        Ok(self.into_inner())
+    }
+
+    fn into_reader(self) -> Result<Self::Reader, StdinError> {
+        Ok(Cursor::new(self.to_string().into_bytes()))
+    }
+
+    fn source_kind(&self) -> SourceKind {
+        MaybeStdinFromSource::source_kind(self)
+    }
+}
+
+#[cfg(feature = "vec")]
+impl<T: Display, const D: char, const STDIN_DELIMITED: bool> ContentSource
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+{
+    type Value = Vec<T>;
+    type Reader = Cursor<Vec<u8>>;
+
+    fn contents(self) -> Result<Vec<T>, StdinError> {
+        Ok(self.into_inner())
+    }
+
+    fn into_reader(self) -> Result<Self::Reader, StdinError> {
+        Ok(Cursor::new(self.to_string().into_bytes()))
+    }
+
+    fn source_kind(&self) -> SourceKind {
+        MaybeStdinVec::source_kind(self)
+    }
+}
+
+#[cfg(feature = "file")]
+impl<T> ContentSource for FileOrStdin<T>
+where
+    T: std::str::FromStr,
+    T::Err: Display,
+{
+    type Value = T;
+    type Reader = Self;
+
+    fn contents(self) -> Result<T, StdinError> {
+        FileOrStdin::contents(self)
+    }
+
+    fn into_reader(self) -> Result<Self, StdinError> {
+        self.into_opened()
+    }
+
+    fn source_kind(&self) -> SourceKind {
+        FileOrStdin::source_kind(self)
+    }
+}
+
+#[cfg(feature = "file")]
+impl ContentSource for PathOrStdin {
+    type Value = String;
+    type Reader = io::BufReader<std::fs::File>;
+
+    fn contents(self) -> Result<String, StdinError> {
+        let contents = std::fs::read(self.path()).map_err(StdinError::file(self.path(), "read"))?;
+        Ok(Utf8Policy::current_default().decode(contents)?)
+    }
+
+    fn into_reader(self) -> Result<Self::Reader, StdinError> {
+        let file =
+            std::fs::File::open(self.path()).map_err(StdinError::file(self.path(), "open"))?;
+        Ok(io::BufReader::new(file))
+    }
+
+    fn source_kind(&self) -> SourceKind {
+        PathOrStdin::source_kind(self)
+    }
+}
+
+#[cfg(all(feature = "maybe-stdin", feature = "vec", feature = "file"))]
+#[test]
+fn test_content_source() {
+    use std::io::Read;
+
+    fn read_all<S: ContentSource>(input: S) -> String {
+        let mut contents = String::new();
+        input
+            .into_reader()
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        contents
+    }
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "1\n2\n").unwrap();
+
+    let value: MaybeStdin<u32> = "42".parse().unwrap();
+    assert_eq!(read_all(value), "42");
+    let values: MaybeStdinVec<u32> = "1,2".parse().unwrap();
+    assert_eq!(ContentSource::source_kind(&values), SourceKind::Literal);
+    assert_eq!(read_all(values), "1,2");
+    let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    assert_eq!(read_all(input), "1\n2\n");
+    let input: PathOrStdin = tmp.path().to_str().unwrap().parse().unwrap();
+    assert_eq!(ContentSource::contents(input).unwrap(), "1\n2\n");
+}
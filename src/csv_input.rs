@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use super::{FileOrStdin, SourceKind, StdinError, StdinOpts};
+
+/// Wrapper struct for typed CSV rows read from a file, or from `stdin`
+///
+/// Each row is deserialized into a `T` while the args are parsed. The first row is a header row
+/// naming the fields of `T`, unless the arg takes a [`Csv::without_headers`] parser:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::Csv;
+///
+/// #[derive(Debug, Clone, serde::Deserialize)]
+/// struct Row {
+///     name: String,
+///     count: u32,
+/// }
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     rows: Csv<Row>,
+/// }
+///
+/// let args = Args::parse();
+/// let total: u32 = args.rows.iter().map(|row| row.count).sum();
+/// println!("total={total}");
+/// ```
+///
+/// ```sh
+/// $ ./example rows.csv
+/// $ cat rows.csv | ./example -
+/// ```
+#[derive(Debug, Clone)]
+pub struct Csv<T> {
+    inner: Vec<T>,
+    source: SourceKind,
+}
+
+impl<T: DeserializeOwned> Csv<T> {
+    fn parse_with_opts(s: &str, opts: &StdinOpts, has_headers: bool) -> Result<Self, StdinError> {
+        let input = FileOrStdin::<String>::with_opts(opts.clone())(s)?;
+        let source = input.source_kind();
+        let input = input.contents_bytes()?;
+        let inner = csv::ReaderBuilder::new()
+            .has_headers(has_headers)
+            .from_reader(input.as_slice())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .map_err(|e| StdinError::Deserialize(format!("{e}")))?;
+        Ok(Self { inner, source })
+    }
+
+    /// A `value_parser` that reads the rows as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    /// ```rust
+    /// use clap_stdin::{Csv, StdinOpts};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "name,count\na,1\nb,2\n")?;
+    /// let parse = Csv::<(String, u32)>::with_opts(StdinOpts::new().max_bytes(1024));
+    /// let rows = parse(tmp.path().to_str().unwrap())?;
+    /// assert_eq!(rows[1], ("b".to_string(), 2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts, true)
+    }
+
+    /// A `value_parser` for input without a header row, deserializing each row by position
+    /// instead of by column name
+    /// ```rust
+    /// use clap_stdin::{Csv, StdinOpts};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "a,1\nb,2\n")?;
+    /// let parse = Csv::<(String, u32)>::without_headers(StdinOpts::new());
+    /// let rows = parse(tmp.path().to_str().unwrap())?;
+    /// assert_eq!(rows[1], ("b".to_string(), 2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn without_headers(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts, false)
+    }
+}
+
+impl<T> Csv<T> {
+    /// Where these rows were read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these rows read from stdin (shorthand for checking [`Csv::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the rows from the wrapper
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T: DeserializeOwned> FromStr for Csv<T> {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default(), true)
+    }
+}
+
+impl<T> std::ops::Deref for Csv<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for Csv<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> IntoIterator for Csv<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+#[test]
+fn test_csv() {
+    #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+    struct Row {
+        name: String,
+        count: u32,
+    }
+
+    let _session = crate::StdinSession::with_input("name,count\na,1\nb,2\n");
+    let rows: Csv<Row> = "-".parse().unwrap();
+    assert!(rows.is_stdin());
+    assert_eq!(
+        rows.into_inner(),
+        [
+            Row {
+                name: "a".to_string(),
+                count: 1
+            },
+            Row {
+                name: "b".to_string(),
+                count: 2
+            }
+        ]
+    );
+
+    let _session = crate::StdinSession::with_input("a,1\n");
+    let rows = Csv::<Row>::without_headers(StdinOpts::new())("-").unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "a");
+
+    let _session = crate::StdinSession::with_input("name,count\na,many\n");
+    assert!(matches!(
+        "-".parse::<Csv<Row>>(),
+        Err(StdinError::Deserialize(_))
+    ));
+}
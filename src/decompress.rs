@@ -0,0 +1,93 @@
+//! Transparent decompression of the input, see [`StdinOpts::decompress`](crate::StdinOpts::decompress)
+use std::io::{self, BufRead, BufReader};
+
+use super::BoxedReader;
+
+/// Compression formats recognized by their magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "bzip2")]
+    Bzip2,
+}
+
+impl Format {
+    /// The format of compressed data starting with `magic`, if it is one of the enabled formats
+    fn sniff(magic: &[u8]) -> Option<Self> {
+        match magic {
+            #[cfg(feature = "gzip")]
+            [0x1f, 0x8b, ..] => Some(Format::Gzip),
+            #[cfg(feature = "zstd")]
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Format::Zstd),
+            #[cfg(feature = "bzip2")]
+            [b'B', b'Z', b'h', ..] => Some(Format::Bzip2),
+            _ => None,
+        }
+    }
+}
+
+/// Wrap `reader` in a decoder if its contents are compressed, or return it unchanged
+///
+/// The magic bytes are peeked from the reader's buffer, so nothing is consumed.
+pub(crate) fn decompressed(mut reader: BoxedReader) -> io::Result<BoxedReader> {
+    let Some(format) = Format::sniff(reader.fill_buf()?) else {
+        return Ok(reader);
+    };
+    debug_log!("decompressing {format:?} input");
+    Ok(match format {
+        #[cfg(feature = "gzip")]
+        Format::Gzip => Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "zstd")]
+        Format::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::with_buffer(
+            reader,
+        )?)),
+        #[cfg(feature = "bzip2")]
+        Format::Bzip2 => Box::new(BufReader::new(bzip2::bufread::MultiBzDecoder::new(reader))),
+    })
+}
+
+#[test]
+fn test_uncompressed() {
+    let reader = decompressed(Box::new(io::Cursor::new("plain text"))).unwrap();
+    assert_eq!(io::read_to_string(reader).unwrap(), "plain text");
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"compressed text").unwrap();
+    let compressed = encoder.finish().unwrap();
+    let reader = decompressed(Box::new(io::Cursor::new(compressed.clone()))).unwrap();
+    assert_eq!(io::read_to_string(reader).unwrap(), "compressed text");
+
+    let _session = crate::StdinSession::with_input(compressed);
+    let parse = crate::FileOrStdin::<String>::with_opts(crate::StdinOpts::new().decompress());
+    assert_eq!(parse("-").unwrap().contents().unwrap(), "compressed text");
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    let compressed = zstd::encode_all(&b"compressed text"[..], 0).unwrap();
+    let reader = decompressed(Box::new(io::Cursor::new(compressed))).unwrap();
+    assert_eq!(io::read_to_string(reader).unwrap(), "compressed text");
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn test_bzip2() {
+    use std::io::Read;
+
+    let mut compressed = Vec::new();
+    bzip2::read::BzEncoder::new(&b"compressed text"[..], bzip2::Compression::default())
+        .read_to_end(&mut compressed)
+        .unwrap();
+    let reader = decompressed(Box::new(io::Cursor::new(compressed))).unwrap();
+    assert_eq!(io::read_to_string(reader).unwrap(), "compressed text");
+}
@@ -0,0 +1,64 @@
+//! Support functions for the code generated by `#[derive(FromSource)]`, not a public API
+use std::fmt::Display;
+use std::io;
+use std::str::FromStr;
+
+use super::StdinError;
+
+/// Parse a whole (trimmed) value
+pub fn value<T>(value: &str) -> Result<T, StdinError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    T::from_str(value.trim()).map_err(|e| StdinError::FromStr(format!("{e}")))
+}
+
+/// Parse the items of `value` between each `delimiter`, skipping empty items
+pub fn split<C>(value: &str, delimiter: char) -> Result<C, StdinError>
+where
+    C: IntoIterator + FromIterator<C::Item>,
+    C::Item: FromStr,
+    <C::Item as FromStr>::Err: Display,
+{
+    value
+        .split(delimiter)
+        .filter(|item| !item.trim().is_empty())
+        .map(self::value)
+        .collect()
+}
+
+/// Parse each non-blank line of `stdin`, given with its line number
+pub fn lines<C>(lines: impl Iterator<Item = io::Result<(usize, String)>>) -> Result<C, StdinError>
+where
+    C: IntoIterator + FromIterator<C::Item>,
+    C::Item: FromStr,
+    <C::Item as FromStr>::Err: Display,
+{
+    lines
+        .filter(|line| !matches!(line, Ok((_, line)) if line.trim().is_empty()))
+        .map(|line| {
+            let (n, line) = line?;
+            C::Item::from_str(line.trim())
+                .map_err(|e| StdinError::FromStr(format!("line {n}: {e}")))
+        })
+        .collect()
+}
+
+/// Deserialize a JSON document
+#[cfg(feature = "serde")]
+pub fn json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, StdinError> {
+    serde_json::from_str(value).map_err(|e| StdinError::Deserialize(format!("{e}")))
+}
+
+/// Deserialize a YAML document
+#[cfg(feature = "yaml")]
+pub fn yaml<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, StdinError> {
+    serde_yaml::from_str(value).map_err(|e| StdinError::Deserialize(format!("{e}")))
+}
+
+/// Deserialize a TOML document
+#[cfg(feature = "toml")]
+pub fn toml<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, StdinError> {
+    toml::from_str(value).map_err(|e| StdinError::Deserialize(format!("{e}")))
+}
@@ -0,0 +1,138 @@
+//! [`miette::Diagnostic`] for [`StdinError`], so CLIs reporting errors with miette get an error
+//! code and help text for each failure
+//!
+//! Arg values rejected by `FromStr` are attached as the source code, with a span pointing at
+//! them. Other parse failures have no source spans: the input isn't kept in the error, since it
+//! may be an entire file or pipe.
+use std::fmt::Display;
+
+use super::StdinError;
+
+impl StdinError {
+    /// The miette error code of the variant
+    fn code_name(&self) -> &'static str {
+        match self {
+            StdinError::StdInRepeatedUse => "clap_stdin::repeated_use",
+            StdinError::StdinClosed => "clap_stdin::stdin_closed",
+            StdinError::StdinIsTty => "clap_stdin::stdin_is_tty",
+            StdinError::Timeout(_) => "clap_stdin::timeout",
+            StdinError::InputTooLarge(_) => "clap_stdin::input_too_large",
+            StdinError::EnvNotSet(_) => "clap_stdin::env_not_set",
+            StdinError::EnvNotUnicode(_) => "clap_stdin::env_not_unicode",
+            StdinError::EnvNotNumber(..) => "clap_stdin::env_not_number",
+            #[cfg(feature = "http")]
+            StdinError::HttpStatus(..) | StdinError::Http(..) => "clap_stdin::http",
+            StdinError::File(..) => "clap_stdin::file",
+            #[cfg(feature = "file")]
+            StdinError::PathsNotFound(_) => "clap_stdin::paths_not_found",
+            StdinError::StdIn(_) => "clap_stdin::io",
+            StdinError::FromStr(_) | StdinError::InvalidArg { .. } => "clap_stdin::parse",
+            StdinError::Invalid(_) => "clap_stdin::invalid",
+            #[cfg(feature = "maybe-stdin")]
+            StdinError::Hex(_) => "clap_stdin::hex",
+            #[cfg(feature = "base64")]
+            StdinError::Base64(_) => "clap_stdin::base64",
+            #[cfg(feature = "from-source")]
+            StdinError::FromSource(_) => "clap_stdin::parse",
+            #[cfg(feature = "maybe-stdin")]
+            StdinError::MissingValue => "clap_stdin::missing_value",
+            #[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+            StdinError::NoTerminal => "clap_stdin::no_terminal",
+            #[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+            StdinError::TerminalBusy => "clap_stdin::terminal_busy",
+            #[cfg(feature = "maybe-stdin")]
+            StdinError::NotConfirmed => "clap_stdin::not_confirmed",
+            #[cfg(feature = "serde")]
+            StdinError::Deserialize(_) => "clap_stdin::deserialize",
+            #[cfg(feature = "arrow")]
+            StdinError::Arrow(_) => "clap_stdin::arrow",
+        }
+    }
+
+    /// How the user can fix the failure, if there's a common fix
+    fn help_text(&self) -> Option<&'static str> {
+        Some(match self {
+            StdinError::StdInRepeatedUse => {
+                "only one argument can read from stdin: pass a value or a file to the others"
+            }
+            StdinError::StdinClosed | StdinError::StdinIsTty => {
+                "pipe data in (E.g. `cat input.txt | ...`), or pass a value instead of '-'"
+            }
+            StdinError::Timeout(_) => "pipe data in, or pass a value instead of '-'",
+            StdinError::InputTooLarge(_) => "pass a smaller input",
+            StdinError::EnvNotSet(_) => "set the environment variable, or pass the value directly",
+            StdinError::EnvNotUnicode(_) => "set the environment variable to valid UTF-8 text",
+            StdinError::EnvNotNumber(..) => "set the environment variable to a whole number",
+            StdinError::File(..) => "check the path, or pass '-' to read from stdin",
+            #[cfg(feature = "file")]
+            StdinError::PathsNotFound(_) => "check the paths listed in the input",
+            #[cfg(feature = "maybe-stdin")]
+            StdinError::MissingValue => "pass a value, or pass '-' to read from stdin",
+            #[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+            StdinError::NoTerminal => "run the command in a terminal, or pass a value",
+            _ => return None,
+        })
+    }
+}
+
+impl miette::Diagnostic for StdinError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(self.code_name()))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.help_text()
+            .map(|help| Box::new(help) as Box<dyn Display + 'a>)
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        match self {
+            StdinError::InvalidArg { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        match self {
+            StdinError::InvalidArg { value, message } => {
+                let span = (0, value.inner().len());
+                let label = miette::LabeledSpan::new_with_span(Some(message.clone()), span);
+                Some(Box::new(std::iter::once(label)))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_diagnostic() {
+    use miette::Diagnostic;
+
+    let error = StdinError::StdinClosed;
+    assert_eq!(
+        error.code().unwrap().to_string(),
+        "clap_stdin::stdin_closed"
+    );
+    assert!(error.help().unwrap().to_string().contains("pass a value"));
+
+    let error = StdinError::FromStr("invalid digit found in string".into());
+    assert_eq!(error.code().unwrap().to_string(), "clap_stdin::parse");
+    assert!(error.help().is_none());
+    assert!(error.labels().is_none());
+}
+
+#[cfg(feature = "maybe-stdin")]
+#[test]
+fn test_diagnostic_invalid_arg() {
+    use miette::Diagnostic;
+
+    let error = "many".parse::<crate::MaybeStdin<u32>>().unwrap_err();
+    assert_eq!(error.code().unwrap().to_string(), "clap_stdin::parse");
+    let source = error.source_code().unwrap();
+    let label = error.labels().unwrap().next().unwrap();
+    assert_eq!((label.offset(), label.len()), (0, 4));
+    assert_eq!(label.label(), Some("invalid digit found in string"));
+    let contents = source.read_span(label.inner(), 0, 0).unwrap();
+    assert_eq!(contents.name(), Some("argument"));
+    assert_eq!(contents.data(), b"many");
+}
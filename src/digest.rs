@@ -0,0 +1,52 @@
+/// Digest algorithms that can be computed while reading the input, see [`StdinOpts::digest`](crate::StdinOpts::digest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DigestAlgorithm {
+    /// SHA-256 (requires the `sha2` feature)
+    #[cfg(feature = "sha2")]
+    Sha256,
+    /// BLAKE3 (requires the `blake3` feature)
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+/// Incremental digest of the bytes read so far
+#[derive(Clone)]
+pub(crate) enum Hasher {
+    #[cfg(feature = "sha2")]
+    Sha256(sha2::Sha256),
+    #[cfg(feature = "blake3")]
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    pub(crate) fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            #[cfg(feature = "sha2")]
+            DigestAlgorithm::Sha256 => Hasher::Sha256(<sha2::Sha256 as sha2::Digest>::new()),
+            #[cfg(feature = "blake3")]
+            DigestAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        match self {
+            #[cfg(feature = "sha2")]
+            Hasher::Sha256(hasher) => sha2::Digest::update(hasher, bytes),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+        }
+    }
+
+    /// The digest of the bytes read so far
+    pub(crate) fn finalize(&self) -> [u8; 32] {
+        match self {
+            #[cfg(feature = "sha2")]
+            Hasher::Sha256(hasher) => sha2::Digest::finalize(hasher.clone()).into(),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(hasher) => hasher.finalize().into(),
+        }
+    }
+}
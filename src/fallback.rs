@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+use super::guard::ensure_stdin_unclaimed;
+use super::session::stdin_is_terminal;
+use super::{MaybeStdin, Source, SourceKind, StdinError, Trim};
+
+/// Builder for a value resolved from a prioritized chain of sources
+///
+/// Each source is tried in the order it was added, and the first one that provides a value wins.
+/// The origin of the value is recorded in the resulting [`MaybeStdin`]'s [`SourceKind`].
+/// - [`Fallback::arg`]: a CLI arg value, if it was given (`"-"` reads from stdin as usual)
+/// - [`Fallback::env`]: an environment variable, if it is set and not empty
+/// - [`Fallback::stdin`]: `stdin`, if it is piped (not a terminal), unread and not empty
+/// - [`Fallback::default_value`]: a default value
+///
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{Fallback, SourceKind};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     token: Option<String>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse_from(["example"]);
+/// let token = Fallback::new()
+///     .arg(args.token)
+///     .env("EXAMPLE_API_TOKEN")
+///     .default_value(String::from("anonymous"))
+///     .resolve()?;
+/// assert_eq!(token.source_kind(), SourceKind::Default);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Fallback<T> {
+    steps: Vec<Step<T>>,
+    trim: Trim,
+}
+
+enum Step<T> {
+    Arg(Option<String>),
+    Env(String),
+    Stdin,
+    Default(T),
+}
+
+impl<T> Fallback<T> {
+    /// Start an empty chain of sources
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            trim: Trim::Whitespace,
+        }
+    }
+
+    /// Trim values from the environment and `stdin` according to `trim` before parsing them,
+    /// instead of removing all leading and trailing whitespace
+    pub fn trim(mut self, trim: Trim) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Use the CLI arg value, if one was given
+    pub fn arg(mut self, value: Option<impl Into<String>>) -> Self {
+        self.steps.push(Step::Arg(value.map(Into::into)));
+        self
+    }
+
+    /// Use the value of the environment variable `name`, if it is set and not empty
+    pub fn env(mut self, name: impl Into<String>) -> Self {
+        self.steps.push(Step::Env(name.into()));
+        self
+    }
+
+    /// Use the contents of `stdin`, if it is piped, unread and not empty
+    pub fn stdin(mut self) -> Self {
+        self.steps.push(Step::Stdin);
+        self
+    }
+
+    /// Use `value` (usually the last source in the chain)
+    pub fn default_value(mut self, value: T) -> Self {
+        self.steps.push(Step::Default(value));
+        self
+    }
+
+    /// Try each source in order, returning the first value provided
+    ///
+    /// Fails with [`StdinError::MissingValue`] if none of the sources provided a value
+    pub fn resolve(self) -> Result<MaybeStdin<T>, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let trim = self.trim;
+        let parse = |value: &str, kind| {
+            T::from_str(trim.apply(value))
+                .map_err(|e| StdinError::FromStr(format!("{e}")))
+                .map(|val| MaybeStdin::from_value(val, kind))
+        };
+        for step in self.steps {
+            match step {
+                Step::Arg(Some(value)) => return MaybeStdin::from_str(&value),
+                Step::Arg(None) => debug_log!("fallback: no arg value, trying the next source"),
+                Step::Env(name) => match std::env::var(&name) {
+                    Ok(value) if !value.is_empty() => {
+                        debug_log!("fallback: using environment variable {name}");
+                        return parse(&value, SourceKind::Env(name));
+                    }
+                    Err(std::env::VarError::NotUnicode(_)) => {
+                        return Err(StdinError::EnvNotUnicode(name))
+                    }
+                    _ => debug_log!("fallback: environment variable {name} is unset or empty"),
+                },
+                Step::Stdin => {
+                    if stdin_is_terminal() || ensure_stdin_unclaimed().is_err() {
+                        debug_log!("fallback: stdin is a terminal or was already read, skipping");
+                        continue;
+                    }
+                    match Source::stdin().get_value(std::any::type_name::<Self>()) {
+                        Ok(value) if !value.trim().is_empty() => {
+                            debug_log!("fallback: using stdin ({} bytes)", value.len());
+                            return parse(&value, SourceKind::Stdin);
+                        }
+                        Ok(_) | Err(StdinError::StdinClosed) => {
+                            debug_log!("fallback: stdin is empty, skipping")
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Step::Default(value) => {
+                    debug_log!("fallback: using the default value");
+                    return Ok(MaybeStdin::from_value(value, SourceKind::Default));
+                }
+            }
+        }
+        Err(StdinError::MissingValue)
+    }
+}
+
+impl<T> Default for Fallback<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_fallback_order() {
+    crate::opts::set_test_env("CLAP_STDIN_TEST_FALLBACK", "2");
+
+    let value = Fallback::<u32>::new()
+        .arg(Some("1"))
+        .env("CLAP_STDIN_TEST_FALLBACK")
+        .resolve()
+        .unwrap();
+    assert_eq!((*value, value.source_kind()), (1, SourceKind::Literal));
+
+    let value = Fallback::<u32>::new()
+        .arg(None::<String>)
+        .env("CLAP_STDIN_TEST_FALLBACK")
+        .default_value(3)
+        .resolve()
+        .unwrap();
+    assert_eq!(
+        (*value, value.source_kind()),
+        (2, SourceKind::Env("CLAP_STDIN_TEST_FALLBACK".into()))
+    );
+
+    let value = Fallback::<u32>::new()
+        .env("CLAP_STDIN_TEST_FALLBACK_UNSET")
+        .default_value(3)
+        .resolve()
+        .unwrap();
+    assert_eq!((*value, value.source_kind()), (3, SourceKind::Default));
+
+    crate::opts::set_test_env("CLAP_STDIN_TEST_FALLBACK_PADDED", "  padded");
+    let value = Fallback::<String>::new()
+        .env("CLAP_STDIN_TEST_FALLBACK_PADDED")
+        .trim(Trim::None)
+        .resolve()
+        .unwrap();
+    assert_eq!(*value, "  padded");
+
+    assert!(matches!(
+        Fallback::<u32>::new().arg(None::<String>).resolve(),
+        Err(StdinError::MissingValue)
+    ));
+}
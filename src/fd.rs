@@ -0,0 +1,73 @@
+//! Inherited file descriptor sources (`fd:N`), see [`FileOrStdin`](crate::FileOrStdin)
+use std::fs::File;
+use std::io;
+
+/// The descriptor number of an `fd:N` arg value
+pub(crate) fn parse_fd(value: &str) -> Option<u32> {
+    value.strip_prefix("fd:")?.parse().ok()
+}
+
+/// Open an independent handle to the inherited descriptor `fd`, so the descriptor itself stays
+/// open
+///
+/// Only Unix can open a descriptor by number without trusting that it's valid, elsewhere this
+/// fails with [`io::ErrorKind::Unsupported`].
+pub(crate) fn open_fd(fd: u32) -> io::Result<File> {
+    debug_log!("opening inherited descriptor {fd}");
+    #[cfg(unix)]
+    {
+        File::open(format!("/dev/fd/{fd}"))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = fd;
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[test]
+fn test_parse_fd() {
+    assert_eq!(parse_fd("fd:3"), Some(3));
+    assert_eq!(parse_fd("fd:"), None);
+    assert_eq!(parse_fd("fd:-1"), None);
+    assert_eq!(parse_fd("/dev/fd/3"), None);
+}
+
+#[cfg(all(unix, feature = "file"))]
+#[test]
+fn test_fd_source() {
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    let mut tmp = tempfile::tempfile().unwrap();
+    tmp.write_all(b"from a descriptor").unwrap();
+    let fd = tmp.as_raw_fd();
+    let input: crate::FileOrStdin = format!("fd:{fd}").parse().unwrap();
+    assert_eq!(input.source_kind(), crate::SourceKind::Fd(fd as u32));
+    assert!(!input.is_file());
+    assert_eq!(input.contents().unwrap(), "from a descriptor");
+    // The descriptor itself stays open
+    writeln!(tmp).unwrap();
+}
+
+#[cfg(all(unix, feature = "maybe-stdin"))]
+#[test]
+fn test_fd_wrappers() {
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    let mut tmp = tempfile::tempfile().unwrap();
+    tmp.write_all(b"42").unwrap();
+    let value = format!("fd:{}", tmp.as_raw_fd());
+    let source: crate::Source = value.parse().unwrap();
+    assert_eq!(
+        source.source_kind(),
+        crate::SourceKind::Fd(tmp.as_raw_fd() as u32)
+    );
+    assert_eq!(source.contents().unwrap(), "42");
+    let number: crate::MaybeStdin<u32> = value.parse().unwrap();
+    assert_eq!(*number, 42);
+
+    let escaped: crate::Source = "\\fd:3".parse().unwrap();
+    assert_eq!(escaped.as_arg_str(), Some("fd:3"));
+}
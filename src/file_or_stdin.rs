@@ -1,10 +1,33 @@
+use std::ffi::OsString;
+use std::io::{self, BufRead, Read};
 use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "tokio")]
 use tokio::io::AsyncReadExt;
 
-use super::{Source, StdinError};
+use super::bom::SkipBom;
+#[cfg(feature = "serde")]
+use super::bom::BOM;
+#[cfg(any(feature = "sha2", feature = "blake3"))]
+use super::digest::Hasher;
+use super::fd::open_fd;
+#[cfg(any(feature = "async", feature = "tokio"))]
+use super::opts::max_bytes_limit;
+#[cfg(feature = "indicatif")]
+use super::progress::ProgressCallback;
+#[cfg(feature = "tokio")]
+use super::session::take_session_input;
+#[cfg(feature = "mmap")]
+use super::MappedContents;
+use super::{
+    decode_lines, number_lines, read_to_end_limited, BoxedReader, ReadStats, RetryInterrupted,
+    SharedStdin, Source, SourceKind, Stdin, StdinError, StdinOpts, Trim,
+};
+#[cfg(feature = "async")]
+use super::{futures_stdin, read_to_end_futures};
 
 /// Wrapper struct to either read in a file or contents from `stdin`
 ///
@@ -35,28 +58,243 @@ use super::{Source, StdinError};
 /// $ ./example input.txt
 /// 1 2 3 4
 /// ```
-#[derive(Debug, Clone)]
+///
+/// For shell completions generated with `clap_complete` to complete file paths, give the arg a
+/// value hint: `#[arg(value_hint = clap::ValueHint::FilePath)]` (or
+/// [`StdinArgExt::file_or_stdin`](crate::StdinArgExt::file_or_stdin) with the `clap` feature).
+///
+/// Besides paths (including `/dev/fd/N` from process substitution), an inherited file descriptor
+/// can be passed as `fd:N`, so several args can each read their own stream:
+/// ```sh
+/// $ ./example fd:3 3< input.txt
+/// ```
+///
+/// `FileOrStdin` also implements [`Read`] and [`BufRead`], opening the file (or claiming `stdin`)
+/// on the first read, so it can be handed directly to reader-consuming APIs:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::FileOrStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut args = Args::parse();
+/// std::io::copy(&mut args.input, &mut std::io::stdout())?;
+/// # Ok(())
+/// # }
+/// ```
 pub struct FileOrStdin<T = String> {
-    source: Source,
+    input: Input,
+    opts: StdinOpts,
+    /// Were the environment overrides applied to `opts`, which happens before the first read
+    overridden: bool,
+    reader: Option<BoxedReader>,
+    stats: ReadStats,
+    started: Option<Instant>,
+    #[cfg(any(feature = "sha2", feature = "blake3"))]
+    hasher: Option<Hasher>,
     _type: PhantomData<T>,
 }
 
+/// Clones the source only: the clone starts reading from the beginning of the file, or of the
+/// contents of `stdin` kept for the clones of a [`SharedStdin`](crate::SharedStdin) (otherwise
+/// reading `stdin` again fails with [`StdinError::StdInRepeatedUse`])
+impl<T> Clone for FileOrStdin<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.input.clone(), self.opts.clone())
+    }
+}
+
+/// Where a [`FileOrStdin`] reads from, resolved from the arg value
+#[derive(Clone)]
+enum Input {
+    /// `stdin`, with its contents kept for the clones if it's shared
+    Stdin(Option<SharedStdin>),
+    /// The exact path of the file, which may not be valid UTF-8
+    File(PathBuf),
+    /// An inherited descriptor, with the `fd:N` arg value
+    Fd { fd: u32, value: String },
+    #[cfg(feature = "http")]
+    Url(String),
+    /// The arg value is used as the contents, see [`StdinOpts::literal_fallback`]
+    Literal(OsString),
+}
+
+impl std::fmt::Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Stdin(_) => f.write_str("Stdin"),
+            Input::File(path) => f.debug_tuple("File").field(path).finish(),
+            Input::Fd { fd, .. } => f.debug_tuple("Fd").field(fd).finish(),
+            #[cfg(feature = "http")]
+            Input::Url(url) => f.debug_tuple("Url").field(url).finish(),
+            Input::Literal(value) => f.debug_tuple("Literal").field(value).finish(),
+        }
+    }
+}
+
 impl<T> FileOrStdin<T> {
+    /// Create a `FileOrStdin` that reads from the given file path
+    ///
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// let input: FileOrStdin = FileOrStdin::from_path("input.txt");
+    /// assert!(input.is_file());
+    /// assert_eq!(input.filename(), "input.txt");
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        Self::new(Input::File(path.as_ref().to_owned()), StdinOpts::default())
+    }
+
+    fn from_source(source: Source, opts: StdinOpts) -> Self {
+        let input = match source {
+            Source::Stdin(_) => Input::Stdin(None),
+            Source::SharedStdin(stdin) => Input::Stdin(Some(stdin)),
+            Source::Path(path) => Input::File(path),
+            Source::Fd(fd) => Input::Fd {
+                fd,
+                value: format!("fd:{fd}"),
+            },
+            #[cfg(feature = "http")]
+            Source::Arg(value) if super::http::is_url(&value) => Input::Url(value),
+            Source::Arg(value) => Input::File(value.into()),
+        };
+        let input = match input {
+            Input::File(path) if opts.literal_fallback && !path.is_file() => {
+                #[cfg(feature = "testing")]
+                crate::guard::report_provenance(
+                    crate::testing::PROVENANCE_LITERAL_PREFIX,
+                    std::any::type_name::<Self>(),
+                );
+                Input::Literal(path.into_os_string())
+            }
+            input => input,
+        };
+        debug_log!(
+            "{}: resolved to {}",
+            std::any::type_name::<Self>(),
+            match &input {
+                Input::Stdin(_) => "stdin",
+                Input::File(_) => "a file",
+                Input::Fd { .. } => "a descriptor",
+                #[cfg(feature = "http")]
+                Input::Url(_) => "a URL",
+                Input::Literal(_) => "a literal (no such file)",
+            }
+        );
+        Self::new(input, opts)
+    }
+
+    fn new(input: Input, opts: StdinOpts) -> Self {
+        Self {
+            input,
+            #[cfg(any(feature = "sha2", feature = "blake3"))]
+            hasher: opts.digest.map(Hasher::new),
+            opts,
+            overridden: false,
+            reader: None,
+            stats: ReadStats::default(),
+            started: None,
+            _type: PhantomData,
+        }
+    }
+
+    /// A `value_parser` that reads the file or stdin as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::{FileOrStdin, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(1 << 20)))]
+    ///     input: FileOrStdin,
+    /// }
+    /// ```
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| {
+            let input = Self::from_source(opts.source(s), opts.clone());
+            match opts.validator {
+                Some(_) => input.into_validated(),
+                None => Ok(input),
+            }
+        }
+    }
+
+    /// Where this value is read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    /// (or [`SourceKind::Literal`] with [`StdinOpts::literal_fallback`], and `SourceKind::Url`
+    /// with the `http` feature)
+    pub fn source_kind(&self) -> SourceKind {
+        match &self.input {
+            Input::Stdin(_) => SourceKind::Stdin,
+            Input::File(path) => SourceKind::File(path.clone()),
+            Input::Fd { fd, .. } => SourceKind::Fd(*fd),
+            #[cfg(feature = "http")]
+            Input::Url(url) => SourceKind::Url(url.clone()),
+            Input::Literal(_) => SourceKind::Literal,
+        }
+    }
+
     /// Was this value read from stdin
     pub fn is_stdin(&self) -> bool {
-        matches!(self.source, Source::Stdin(_))
+        matches!(self.input, Input::Stdin(_))
     }
 
     /// Was this value read from a file (path passed in from argument values)
     pub fn is_file(&self) -> bool {
-        !self.is_stdin()
+        matches!(self.input, Input::File(_))
+    }
+
+    /// The path of the file this value is read from, or `None` for `stdin` (and descriptors,
+    /// URLs and literal values)
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// let input: FileOrStdin = "input.txt".parse().unwrap();
+    /// assert_eq!(input.path(), Some(Path::new("input.txt")));
+    /// let input: FileOrStdin = "-".parse().unwrap();
+    /// assert_eq!(input.path(), None);
+    /// ```
+    pub fn path(&self) -> Option<&Path> {
+        match &self.input {
+            Input::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The size of the input in bytes when it's known without reading it: the size of a file
+    /// (before any decompression), or the length of a literal value
+    ///
+    /// Meant for preallocating buffers and progress displays, since a file may change size
+    /// before it's read.
+    pub fn len_hint(&self) -> Option<u64> {
+        match &self.input {
+            Input::Literal(value) => Some(value.len() as u64),
+            _ => self
+                .path()
+                .and_then(|path| std::fs::metadata(path).ok())
+                .map(|metadata| metadata.len()),
+        }
     }
 
     /// The value passed to this arg (Either "-" for stdin or a filepath)
     pub fn filename(&self) -> &str {
-        match &self.source {
-            Source::Stdin(_) => "-",
-            Source::Arg(path) => path,
+        match &self.input {
+            Input::Stdin(_) => self.opts.sentinel.as_deref().unwrap_or("-"),
+            Input::File(path) => path.to_str().unwrap_or_default(),
+            Input::Fd { value, .. } => value,
+            #[cfg(feature = "http")]
+            Input::Url(url) => url,
+            Input::Literal(value) => value.to_str().unwrap_or_default(),
         }
     }
 
@@ -66,11 +304,269 @@ impl<T> FileOrStdin<T> {
         T: FromStr,
         <T as FromStr>::Err: std::fmt::Display,
     {
-        use std::io::Read;
-        let mut reader = self.into_reader()?;
-        let mut input = String::new();
-        let _ = reader.read_to_string(&mut input)?;
-        T::from_str(input.trim_end()).map_err(|e| StdinError::FromStr(format!("{e}")))
+        self.contents_with_stats().map(|(value, _)| value)
+    }
+
+    /// Read the entire contents like [`FileOrStdin::contents`], failing with
+    /// [`StdinError::InputTooLarge`] once more than `max_bytes` are read
+    ///
+    /// The stricter of `max_bytes` and a limit configured with [`StdinOpts::max_bytes`] applies.
+    /// Nothing beyond the limit is buffered, so an accidental multi-gigabyte pipe fails early.
+    /// ```rust
+    /// use clap_stdin::{FileOrStdin, StdinError};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "too large")?;
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// assert!(matches!(input.contents_limited(4), Err(StdinError::InputTooLarge(4))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contents_limited(mut self, max_bytes: u64) -> Result<T, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let max_bytes = self
+            .resolved_opts()?
+            .max_bytes
+            .map_or(max_bytes, |max| max.min(max_bytes));
+        self.opts.max_bytes = Some(max_bytes);
+        self.contents()
+    }
+
+    /// Read the entire contents from the input source like [`FileOrStdin::contents`],
+    /// also returning the [`ReadStats`] of the read
+    pub fn contents_with_stats(mut self) -> Result<(T, ReadStats), StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let input = self.read_all()?;
+        let input = self.opts.normalize(self.opts.utf8_policy().decode(input)?);
+        let value = T::from_str(self.opts.trim_or(Trim::End).apply(&input))
+            .map_err(|e| StdinError::FromStr(format!("{e}")))?;
+        self.stats.items_parsed = 1;
+        debug_log!(
+            "{}: read {} bytes from {} in {:?}",
+            std::any::type_name::<Self>(),
+            self.stats.bytes_read,
+            self.source_kind().name(),
+            self.stats.duration
+        );
+        Ok((value, self.stats()))
+    }
+
+    /// Read the entire raw contents from the input source, without any UTF-8 validation
+    ///
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, [0xff, 0x00, 0x89])?;
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// assert_eq!(input.contents_bytes()?, [0xff, 0x00, 0x89]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contents_bytes(mut self) -> Result<Vec<u8>, StdinError> {
+        self.read_all()
+    }
+
+    /// Map a file into memory instead of copying its contents, for zero-copy access to
+    /// multi-gigabyte inputs
+    ///
+    /// `stdin`, descriptors, URLs and literal values can't be mapped, so they're read into a
+    /// buffer like [`FileOrStdin::contents_bytes`]. So are files read with decompression,
+    /// transcoding, a digest or a rate limit, which need every byte to go through a reader.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified (by this or another process) while the
+    /// returned contents are alive. Reading a map of a modified file is undefined behavior, and
+    /// reading past the end of a truncated file crashes the process with `SIGBUS`.
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "large input")?;
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// // SAFETY: the temp file isn't modified while it's mapped
+    /// let contents = unsafe { input.mmap()? };
+    /// assert!(contents.is_mapped());
+    /// assert_eq!(&contents[..], b"large input");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub unsafe fn mmap(mut self) -> Result<MappedContents, StdinError> {
+        match &self.input {
+            Input::File(path) if self.opts.reads_verbatim() => {
+                let file = std::fs::File::open(path).map_err(StdinError::file(path, "open"))?;
+                // SAFETY: the caller guarantees the file isn't modified while it's mapped
+                let contents = unsafe { MappedContents::map(&file, &self.opts)? };
+                self.stats.bytes_read = contents.len() as u64;
+                if let Some(callback) = &self.opts.progress {
+                    callback.report(self.stats.bytes_read, Some(self.stats.bytes_read));
+                }
+                debug_log!(
+                    "{}: mapped {} bytes from a file",
+                    std::any::type_name::<Self>(),
+                    contents.len()
+                );
+                Ok(contents)
+            }
+            _ => self.read_all().map(MappedContents::buffered),
+        }
+    }
+
+    /// Read the entire contents from the input source, deserializing them as JSON
+    ///
+    /// Use [`Json`](crate::Json) as the field type to deserialize while the args are parsed instead.
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, r#"{"name": "example"}"#)?;
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// let config: Config = input.deserialize_json()?;
+    /// assert_eq!(config.name, "example");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize_json<U: serde::de::DeserializeOwned>(mut self) -> Result<U, StdinError> {
+        let input = self.read_text()?;
+        serde_json::from_slice(&input).map_err(|e| StdinError::Deserialize(format!("{e}")))
+    }
+
+    /// Read the entire contents from the input source, deserializing them as YAML
+    ///
+    /// Use [`Yaml`](crate::Yaml) as the field type to deserialize while the args are parsed instead.
+    #[cfg(feature = "yaml")]
+    pub fn deserialize_yaml<U: serde::de::DeserializeOwned>(mut self) -> Result<U, StdinError> {
+        let input = self.read_text()?;
+        serde_yaml::from_slice(&input).map_err(|e| StdinError::Deserialize(format!("{e}")))
+    }
+
+    /// Read the entire contents from the input source, deserializing them as TOML
+    ///
+    /// Use [`Toml`](crate::Toml) as the field type to deserialize while the args are parsed instead.
+    #[cfg(feature = "toml")]
+    pub fn deserialize_toml<U: serde::de::DeserializeOwned>(mut self) -> Result<U, StdinError> {
+        let input = self.read_text()?;
+        let input = self.opts.utf8_policy().decode(input)?;
+        toml::from_str(&input).map_err(|e| StdinError::Deserialize(format!("{e}")))
+    }
+
+    /// Read the rest of the input, honouring the configured limits
+    fn read_all(&mut self) -> Result<Vec<u8>, StdinError> {
+        let mut input = Vec::new();
+        if let Err(e) = self.read_to_end(&mut input) {
+            return Err(match self.stats.hit_limit {
+                true => StdinError::InputTooLarge(self.opts.max_bytes.unwrap_or_default()),
+                false => StdinError::from_io(e),
+            });
+        }
+        if self.is_stdin() && input.is_empty() {
+            return Err(StdinError::StdinClosed);
+        }
+        Ok(input)
+    }
+
+    /// Read the rest of the input as text to deserialize, without a leading byte order mark
+    /// (unless [`StdinOpts::keep_bom`] is set)
+    #[cfg(feature = "serde")]
+    fn read_text(&mut self) -> Result<Vec<u8>, StdinError> {
+        let input = self.read_all()?;
+        match !self.opts.keep_bom && input.starts_with(BOM) {
+            true => Ok(input[BOM.len()..].to_vec()),
+            false => Ok(input),
+        }
+    }
+
+    /// Iterate over the lines of the input (without line endings), streaming them from the file
+    /// or `stdin` instead of loading the entire input into memory
+    ///
+    /// Lines are skipped as configured by [`StdinOpts::skip_blank`] and
+    /// [`StdinOpts::comment_prefix`].
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "first\nsecond\n")?;
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// let mut count = 0;
+    /// for line in input.lines() {
+    ///     let _line: String = line?;
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lines(self) -> impl Iterator<Item = Result<String, StdinError>> {
+        self.lines_numbered()
+            .map(|line| line.map(|(_, line)| line).map_err(StdinError::from_io))
+    }
+
+    /// Iterate over the lines of the input, each with its 1-based line number
+    ///
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "first\nsecond\n")?;
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// for line in input.lines_numbered() {
+    ///     let (number, line) = line?;
+    ///     println!("{number}: {line}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lines_numbered(self) -> impl Iterator<Item = io::Result<(usize, String)>> {
+        let utf8 = self.opts.utf8_policy();
+        let skipped = self.opts.line_filter();
+        let keep_bom = self.opts.keep_bom;
+        number_lines(decode_lines(SkipBom::unless_kept(self, keep_bom), utf8))
+            .filter(move |line| !matches!(line, Ok((_, line)) if skipped(line)))
+    }
+
+    /// Statistics of the input read so far through the [`Read`] and [`BufRead`] impls
+    ///
+    /// ```rust
+    /// use std::io::Read;
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "contents")?;
+    /// let mut input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// std::io::copy(&mut input, &mut std::io::sink())?;
+    /// assert_eq!(input.stats().bytes_read, 8);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> ReadStats {
+        #[allow(unused_mut)]
+        let mut stats = self.stats;
+        #[cfg(any(feature = "sha2", feature = "blake3"))]
+        {
+            stats.digest = self.hasher.as_ref().map(Hasher::finalize);
+        }
+        stats
     }
 
     /// Create a reader from the source, to allow user flexibility of
@@ -95,8 +591,225 @@ impl<T> FileOrStdin<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn into_reader(self) -> Result<impl std::io::Read, StdinError> {
-        self.source.into_reader()
+    pub fn into_reader(self) -> Result<impl BufRead, StdinError> {
+        self.into_opened()
+    }
+
+    /// Open the input, returning a [`Read`] and [`BufRead`] reader over it
+    ///
+    /// Reading a `FileOrStdin` directly opens it on the first read, reporting a missing file as an
+    /// [`io::Error`]. This opens it up front instead, so such errors surface as a [`StdinError`]
+    /// before any reader-consuming API is called.
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// #[derive(Parser)]
+    /// struct Args {
+    ///     config: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut args = Args::parse();
+    /// let config: serde_json::Value = serde_json::from_reader(args.config.reader()?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reader(&mut self) -> Result<&mut Self, StdinError> {
+        self.open_reader()?;
+        Ok(self)
+    }
+
+    /// Open the reader over the source, failing early if it can't be opened
+    pub(crate) fn into_opened(mut self) -> Result<Self, StdinError> {
+        self.open_reader()?;
+        Ok(self)
+    }
+
+    /// Read the entire input to check it with the validator of [`StdinOpts::validate`], keeping
+    /// it to be read again
+    ///
+    /// The input is read eagerly, while the arg is parsed, up to [`MAX_BYTES_ENV`] (or
+    /// [`StdinOpts::max_bytes`]). It's decoded for the validator with the [`StdinOpts::utf8`]
+    /// policy, so input that isn't valid UTF-8 fails here, unless the policy is lossy.
+    fn into_validated(mut self) -> Result<Self, StdinError> {
+        let max_bytes = self.resolved_opts()?.max_bytes;
+        let contents = read_to_end_limited(self.open_reader()?, max_bytes)?;
+        self.opts
+            .check(&self.opts.utf8_policy().decode(contents.clone())?)?;
+        self.reader = Some(Box::new(io::Cursor::new(contents)));
+        Ok(self)
+    }
+
+    /// The options, with the environment overrides applied once, before the first read
+    fn resolved_opts(&mut self) -> Result<&StdinOpts, StdinError> {
+        if !self.overridden {
+            self.opts = std::mem::take(&mut self.opts).with_env_overrides()?;
+            self.overridden = true;
+        }
+        Ok(&self.opts)
+    }
+
+    /// The reader over the source, opened on first use
+    fn open_reader(&mut self) -> Result<&mut BoxedReader, StdinError> {
+        let reader = match self.reader.take() {
+            Some(reader) => reader,
+            None => {
+                self.resolved_opts()?;
+                self.started = Some(Instant::now());
+                let total = self.len_hint();
+                let reader: BoxedReader = match &self.input {
+                    Input::Literal(value) => {
+                        let reader = io::Cursor::new(value.clone().into_encoded_bytes());
+                        return Ok(self.reader.insert(Box::new(reader)));
+                    }
+                    Input::Stdin(None) => {
+                        Stdin.reader(std::any::type_name::<Self>(), Some(&self.opts))?
+                    }
+                    Input::Stdin(Some(stdin)) => {
+                        stdin.reader(std::any::type_name::<Self>(), Some(&self.opts))?
+                    }
+                    Input::File(path) => Box::new(io::BufReader::new(FileReader {
+                        file: RetryInterrupted(
+                            std::fs::File::open(path).map_err(StdinError::file(path, "open"))?,
+                        ),
+                        path: path.clone(),
+                    })),
+                    Input::Fd { fd, .. } => {
+                        Box::new(io::BufReader::new(RetryInterrupted(open_fd(*fd)?)))
+                    }
+                    #[cfg(feature = "http")]
+                    Input::Url(url) => super::http::fetch(url)?,
+                };
+                let reader = self.opts.progress_reader(reader, total);
+                #[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+                let reader = match self.opts.decompress {
+                    true => super::decompress::decompressed(reader)?,
+                    false => reader,
+                };
+                self.opts.text_reader(reader)
+            }
+        };
+        Ok(self.reader.insert(reader))
+    }
+
+    /// Number of bytes that can still be read before exceeding `max_bytes`
+    fn remaining(&mut self) -> io::Result<Option<usize>> {
+        let Some(max_bytes) = self.resolved_opts()?.max_bytes else {
+            return Ok(None);
+        };
+        let remaining = max_bytes.saturating_sub(self.stats.bytes_read);
+        if remaining == 0 && !self.open_reader()?.fill_buf()?.is_empty() {
+            self.stats.hit_limit = true;
+            return Err(StdinError::InputTooLarge(max_bytes).into());
+        }
+        Ok(Some(usize::try_from(remaining).unwrap_or(usize::MAX)))
+    }
+
+    /// Number of bytes to read at once: at most `len`, and no more than the rate limit allows
+    fn chunk_len(&mut self, len: usize) -> io::Result<usize> {
+        let len = self.remaining()?.map_or(len, |r| r.min(len));
+        Ok(match self.opts.rate_limit {
+            Some(rate) => len.min(usize::try_from(rate).unwrap_or(usize::MAX)),
+            None => len,
+        })
+    }
+
+    /// Record `n` bytes as read, sleeping as needed to stay within the rate limit
+    fn record_read(&mut self, n: usize) {
+        self.stats.bytes_read += n as u64;
+        let Some(started) = self.started else {
+            return;
+        };
+        if let Some(rate) = self.opts.rate_limit {
+            let target = Duration::from_secs_f64(self.stats.bytes_read as f64 / rate as f64);
+            if let Some(wait) = target.checked_sub(started.elapsed()) {
+                std::thread::sleep(wait);
+            }
+        }
+        self.stats.duration = started.elapsed();
+    }
+
+    /// Open the input like [`FileOrStdin::reader`], displaying a progress bar on `stderr` while
+    /// it's read
+    ///
+    /// Files show a bar with their known size, while `stdin` shows a spinner with the throughput.
+    /// The bar is hidden when `stderr` is not a terminal. See [`FileOrStdin::with_progress_bar`]
+    /// to style the bar.
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let mut reader = args.input.into_progress_reader()?;
+    /// std::io::copy(&mut reader, &mut std::io::sink())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "indicatif")]
+    pub fn into_progress_reader(self) -> Result<Self, StdinError> {
+        use indicatif::{ProgressBar, ProgressStyle};
+
+        let progress = match self.len_hint() {
+            Some(total) => ProgressBar::new(total).with_style(
+                ProgressStyle::with_template(
+                    "{bar:40} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+                )
+                .expect("valid progress template"),
+            ),
+            None => ProgressBar::new_spinner().with_style(
+                ProgressStyle::with_template("{spinner} {bytes} ({bytes_per_sec})")
+                    .expect("valid progress template"),
+            ),
+        };
+        self.with_progress_bar(progress).into_opened()
+    }
+
+    /// Advance `bar` with the bytes read from the input, on top of the callback registered with
+    /// [`StdinOpts::on_progress`] (which is still called)
+    ///
+    /// Like the callback, the bar counts the bytes read from the file or `stdin`, and sets its
+    /// length to the size of the input when it's known. If the input was already opened (E.g. to
+    /// check it with [`StdinOpts::validate`]), the bar counts the reads from here on.
+    /// ```rust
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "contents")?;
+    /// let bar = indicatif::ProgressBar::hidden();
+    /// let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    /// input.with_progress_bar(bar.clone()).contents()?;
+    /// assert_eq!(bar.position(), 8);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "indicatif")]
+    pub fn with_progress_bar(mut self, bar: indicatif::ProgressBar) -> Self {
+        let previous = match self.reader {
+            Some(_) => None,
+            None => self.opts.progress.take(),
+        };
+        let callback = ProgressCallback::new(move |read, total| {
+            if let Some(previous) = &previous {
+                previous.report(read, total);
+            }
+            if let Some(total) = total {
+                bar.set_length(total);
+            }
+            bar.set_position(read);
+        });
+        match self.reader.take() {
+            Some(reader) => self.reader = Some(callback.wrap(reader, self.len_hint())),
+            None => self.opts.progress = Some(callback),
+        }
+        self
     }
 
     #[cfg(feature = "tokio")]
@@ -117,15 +830,132 @@ impl<T> FileOrStdin<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn contents_async(self) -> Result<T, StdinError>
+    pub async fn contents_async(mut self) -> Result<T, StdinError>
     where
         T: FromStr,
         <T as FromStr>::Err: std::fmt::Display,
     {
+        let max_bytes = self.resolved_opts()?.max_bytes;
         let mut reader = self.into_async_reader().await?;
-        let mut input = String::new();
-        let _ = reader.read_to_string(&mut input).await?;
-        T::from_str(input.trim_end()).map_err(|e| StdinError::FromStr(format!("{e}")))
+        let mut input = Vec::new();
+        match max_bytes {
+            Some(max_bytes) => {
+                reader
+                    .take(max_bytes.saturating_add(1))
+                    .read_to_end(&mut input)
+                    .await?;
+                if input.len() as u64 > max_bytes {
+                    return Err(StdinError::InputTooLarge(max_bytes));
+                }
+            }
+            None => {
+                reader.read_to_end(&mut input).await?;
+            }
+        }
+        self.parse_contents(input)
+    }
+
+    /// Parse the entire input read by one of the async readers
+    #[cfg(any(feature = "tokio", feature = "async"))]
+    fn parse_contents(&self, input: Vec<u8>) -> Result<T, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        if self.is_stdin() && input.is_empty() {
+            return Err(StdinError::StdinClosed);
+        }
+        let input = self.opts.normalize(self.opts.utf8_policy().decode(input)?);
+        T::from_str(self.opts.trim_or(Trim::End).apply(&input))
+            .map_err(|e| StdinError::FromStr(format!("{e}")))
+    }
+
+    /// Read the entire contents from the input source through the `futures` I/O traits,
+    /// returning T::from_str
+    ///
+    /// Unlike [`FileOrStdin::contents_async`], this doesn't depend on tokio: `stdin` and files are
+    /// read on a background thread, so any async runtime (E.g. smol or async-std) can await it.
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::FileOrStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     input: FileOrStdin,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let input = futures_lite::future::block_on(args.input.contents_futures())?;
+    /// println!("input={input}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn contents_futures(mut self) -> Result<T, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let max_bytes = self.resolved_opts()?.max_bytes;
+        let reader = self.into_futures_reader().await?;
+        let input = read_to_end_futures(reader, max_bytes).await?;
+        self.parse_contents(input)
+    }
+
+    /// Create a [`futures_lite::AsyncRead`] (`futures::io::AsyncRead`) reader from the source,
+    /// to allow user flexibility of how to read and parse
+    #[cfg(feature = "async")]
+    pub async fn into_futures_reader(
+        &self,
+    ) -> Result<impl futures_lite::AsyncRead + Unpin + Send, StdinError> {
+        let mut input: std::pin::Pin<Box<dyn futures_lite::AsyncRead + Send>> = match &self.input {
+            Input::Stdin(stdin) => match stdin.as_ref().and_then(SharedStdin::kept) {
+                Some(contents) => Box::pin(futures_lite::io::Cursor::new(contents)),
+                None => match (
+                    self.opts.prepare_stdin(std::any::type_name::<Self>())?,
+                    stdin,
+                ) {
+                    (Some(typed), Some(stdin)) => {
+                        Box::pin(futures_lite::io::Cursor::new(stdin.keep(typed)))
+                    }
+                    (Some(typed), None) => Box::pin(futures_lite::io::Cursor::new(typed)),
+                    (None, Some(stdin)) => {
+                        let max_bytes = max_bytes_limit(Some(&self.opts))?;
+                        let input = read_to_end_futures(futures_stdin(), max_bytes).await?;
+                        Box::pin(futures_lite::io::Cursor::new(stdin.keep(input)))
+                    }
+                    (None, None) => futures_stdin(),
+                },
+            },
+            Input::Literal(value) => Box::pin(futures_lite::io::Cursor::new(
+                value.clone().into_encoded_bytes(),
+            )),
+            Input::Fd { fd, .. } => {
+                let fd = *fd;
+                Box::pin(blocking::Unblock::new(
+                    blocking::unblock(move || open_fd(fd)).await?,
+                ))
+            }
+            #[cfg(feature = "http")]
+            Input::Url(url) => {
+                let url = url.clone();
+                let reader = blocking::unblock(move || super::http::fetch(&url)).await?;
+                Box::pin(blocking::Unblock::new(reader))
+            }
+            Input::File(filepath) => {
+                let filepath = filepath.clone();
+                let open = StdinError::file(&filepath, "open");
+                let f = blocking::unblock(move || std::fs::File::open(filepath))
+                    .await
+                    .map_err(open)?;
+                Box::pin(blocking::Unblock::new(f))
+            }
+        };
+        if let Some(callback) = &self.opts.progress {
+            input = Box::pin(callback.reader(input, self.len_hint()));
+        }
+        Ok(input)
     }
 
     #[cfg(feature = "tokio")]
@@ -153,27 +983,158 @@ impl<T> FileOrStdin<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn into_async_reader(&self) -> Result<impl tokio::io::AsyncRead, StdinError> {
-        let input: std::pin::Pin<Box<dyn tokio::io::AsyncRead + 'static>> = match &self.source {
-            Source::Stdin => Box::pin(tokio::io::stdin()),
-            Source::Arg(filepath) => {
-                let f = tokio::fs::File::open(filepath).await?;
-                Box::pin(f)
+    pub async fn into_async_reader(
+        &self,
+    ) -> Result<impl tokio::io::AsyncRead + Unpin + Send, StdinError> {
+        let mut input: std::pin::Pin<Box<dyn tokio::io::AsyncRead + Send + 'static>> = match &self
+            .input
+        {
+            Input::Stdin(stdin) => match stdin.as_ref().and_then(SharedStdin::kept) {
+                Some(contents) => Box::pin(io::Cursor::new(contents)),
+                None => {
+                    let typed = self.opts.prepare_stdin(std::any::type_name::<Self>())?;
+                    let mut input: std::pin::Pin<Box<dyn tokio::io::AsyncRead + Send>> =
+                        match (typed, take_session_input()) {
+                            (Some(typed), _) => Box::pin(io::Cursor::new(typed)),
+                            (None, Some(input)) => Box::pin(input),
+                            (None, None) => Box::pin(tokio::io::stdin()),
+                        };
+                    if let Some(stdin) = stdin {
+                        let mut contents = Vec::new();
+                        match max_bytes_limit(Some(&self.opts))? {
+                            Some(max_bytes) => {
+                                (&mut input)
+                                    .take(max_bytes.saturating_add(1))
+                                    .read_to_end(&mut contents)
+                                    .await?;
+                                if contents.len() as u64 > max_bytes {
+                                    return Err(StdinError::InputTooLarge(max_bytes));
+                                }
+                            }
+                            None => {
+                                input.read_to_end(&mut contents).await?;
+                            }
+                        }
+                        input = Box::pin(io::Cursor::new(stdin.keep(contents)));
+                    }
+                    input
+                }
+            },
+            Input::Literal(value) => Box::pin(io::Cursor::new(value.clone().into_encoded_bytes())),
+            Input::Fd { fd, .. } => Box::pin(tokio::fs::File::from_std(open_fd(*fd)?)),
+            // The request blocks, so the body is read on tokio's blocking pool
+            #[cfg(feature = "http")]
+            Input::Url(url) => {
+                let url = url.clone();
+                let max_bytes = max_bytes_limit(Some(&self.opts))?;
+                let body = tokio::task::spawn_blocking(move || {
+                    read_to_end_limited(super::http::fetch(&url)?, max_bytes)
+                })
+                .await
+                .map_err(io::Error::other)??;
+                Box::pin(io::Cursor::new(body))
             }
+            Input::File(path) => Box::pin(
+                tokio::fs::File::open(path)
+                    .await
+                    .map_err(StdinError::file(path, "open"))?,
+            ),
         };
+        if let Some(callback) = &self.opts.progress {
+            input = Box::pin(callback.reader(input, self.len_hint()));
+        }
         Ok(input)
     }
 }
 
+/// Reader over a file, naming the file in its read errors
+struct FileReader {
+    file: RetryInterrupted<std::fs::File>,
+    path: PathBuf,
+}
+
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file
+            .read(buf)
+            .map_err(|e| StdinError::file(&self.path, "read")(e).into())
+    }
+}
+
+impl<T> std::fmt::Debug for FileOrStdin<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileOrStdin")
+            .field("source", &self.input)
+            .field("consumed", &self.started.is_some())
+            .finish()
+    }
+}
+
 impl<T> FromStr for FileOrStdin<T> {
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let source = Source::from_str(s)?;
-        Ok(Self {
-            source,
-            _type: PhantomData,
-        })
+        let opts = StdinOpts::default();
+        Ok(Self::from_source(opts.source(s), opts))
+    }
+}
+
+/// With the `clap` feature, clap parses `FileOrStdin` args from `OsStr` values, so file paths
+/// that aren't valid UTF-8 can be read
+#[cfg(feature = "clap")]
+impl<T: Send + Sync + 'static> clap::builder::ValueParserFactory for FileOrStdin<T> {
+    type Parser =
+        clap::builder::MapValueParser<clap::builder::OsStringValueParser, fn(OsString) -> Self>;
+
+    fn value_parser() -> Self::Parser {
+        use clap::builder::TypedValueParser;
+        clap::builder::OsStringValueParser::new().map(Self::from_os_string as fn(OsString) -> Self)
+    }
+}
+
+#[cfg(feature = "clap")]
+impl<T> FileOrStdin<T> {
+    /// Parse an arg value that may not be valid UTF-8, which is always a file path
+    fn from_os_string(value: OsString) -> Self {
+        match value.into_string() {
+            Ok(value) => {
+                let opts = StdinOpts::default();
+                Self::from_source(opts.source(&value), opts)
+            }
+            Err(path) => Self::from_path(path),
+        }
+    }
+}
+
+impl<T> Read for FileOrStdin<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.chunk_len(buf.len())?;
+        let n = self.open_reader()?.read(&mut buf[..len])?;
+        #[cfg(any(feature = "sha2", feature = "blake3"))]
+        if let Some(hasher) = &mut self.hasher {
+            hasher.update(&buf[..n]);
+        }
+        self.record_read(n);
+        Ok(n)
+    }
+}
+
+impl<T> BufRead for FileOrStdin<T> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let len = self.chunk_len(usize::MAX)?;
+        let buf = self.open_reader()?.fill_buf()?;
+        Ok(&buf[..len.min(buf.len())])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some(reader) = &mut self.reader {
+            #[cfg(any(feature = "sha2", feature = "blake3"))]
+            if let (Some(hasher), Ok(buf)) = (&mut self.hasher, reader.fill_buf()) {
+                hasher.update(&buf[..amt.min(buf.len())]);
+            }
+            reader.consume(amt);
+            self.record_read(amt);
+        }
     }
 }
 
@@ -183,9 +1144,461 @@ fn test_source_methods() {
     assert!(val.is_stdin());
     assert!(!val.is_file());
     assert_eq!(val.filename(), "-");
+    assert_eq!(val.source_kind(), SourceKind::Stdin);
 
     let val: FileOrStdin<String> = "/path/to/something".parse().unwrap();
     assert!(val.is_file());
     assert!(!val.is_stdin());
     assert_eq!(val.filename(), "/path/to/something");
+    assert_eq!(
+        val.source_kind(),
+        SourceKind::File("/path/to/something".into())
+    );
+}
+
+#[test]
+fn test_constructors() {
+    let val: FileOrStdin<String> = FileOrStdin::from_path("/path/to/something");
+    assert!(val.is_file());
+    assert_eq!(val.filename(), "/path/to/something");
+
+    assert!(matches!(Source::stdin(), Source::Stdin(_)));
+    assert!(matches!(Source::literal("value"), Source::Arg(v) if v == "value"));
+    assert!(
+        matches!(Source::path("/tmp/file"), Source::Path(p) if p == std::path::Path::new("/tmp/file"))
+    );
+}
+
+#[test]
+fn test_source_accessors() {
+    let source = Source::literal("value");
+    assert!(!source.is_stdin());
+    assert_eq!(source.as_arg_str(), Some("value"));
+    assert_eq!(source.into_arg_string().as_deref(), Some("value"));
+
+    let source = Source::stdin();
+    assert!(source.is_stdin());
+    assert_eq!(source.as_arg_str(), None);
+    assert_eq!(source.into_arg_string(), None);
+}
+
+#[test]
+fn test_source_try_from() {
+    assert_eq!(String::try_from(Source::literal("value")).unwrap(), "value");
+    assert_eq!(
+        Vec::<u8>::try_from(Source::literal("value")).unwrap(),
+        b"value"
+    );
+    assert_eq!(
+        std::path::PathBuf::try_from(Source::path("/tmp/file")).unwrap(),
+        std::path::Path::new("/tmp/file")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8_path() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(std::ffi::OsStr::from_bytes(b"caf\xe9.txt"));
+    std::fs::write(&path, "contents").unwrap();
+    let source = Source::path(&path);
+    assert_eq!(source.as_path(), Some(path.as_path()));
+    assert_eq!(source.as_arg_str(), None);
+    assert_eq!(std::path::PathBuf::try_from(source).unwrap(), path);
+
+    let input: FileOrStdin = FileOrStdin::from_path(&path);
+    assert_eq!(input.source_kind(), SourceKind::File(path.clone()));
+    assert_eq!(input.contents().unwrap(), "contents");
+
+    #[cfg(feature = "clap")]
+    {
+        let command = clap::Command::new("example")
+            .arg(clap::Arg::new("input").value_parser(clap::value_parser!(FileOrStdin)));
+        let mut matches = command
+            .try_get_matches_from([std::ffi::OsStr::new("example"), path.as_os_str()])
+            .unwrap();
+        let input: FileOrStdin = matches.remove_one("input").unwrap();
+        assert_eq!(input.contents().unwrap(), "contents");
+    }
+}
+
+#[test]
+fn test_read_impl() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\nsecond\n").unwrap();
+
+    let mut input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    let mut line = String::new();
+    input.read_line(&mut line).unwrap();
+    assert_eq!(line, "first\n");
+    // The remaining contents continue from where reading stopped
+    assert_eq!(input.clone().contents().unwrap(), "first\nsecond");
+    assert_eq!(input.contents().unwrap(), "second");
+
+    let mut missing: FileOrStdin = FileOrStdin::from_path("/does/not/exist");
+    assert_eq!(
+        missing.read(&mut [0; 8]).unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+    let error = FileOrStdin::<String>::from_path("/does/not/exist")
+        .reader()
+        .unwrap_err();
+    assert!(matches!(&error, StdinError::File(path, "open", _) if path.ends_with("exist")));
+    assert_eq!(error.to_string(), "failed to open '/does/not/exist'");
+    assert_eq!(
+        std::error::Error::source(&error)
+            .and_then(|source| source.downcast_ref::<io::Error>())
+            .map(io::Error::kind),
+        Some(io::ErrorKind::NotFound)
+    );
+
+    // Read errors name the file too, E.g. reading a directory on Unix
+    #[cfg(unix)]
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let error = FileOrStdin::<String>::from_path(dir.path())
+            .contents_bytes()
+            .unwrap_err();
+        assert!(matches!(&error, StdinError::File(path, "read", _) if path == dir.path()));
+    }
+
+    std::fs::write(&tmp, r#"{"a": 1}"#).unwrap();
+    let mut input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    let value: serde_json::Value = serde_json::from_reader(input.reader().unwrap()).unwrap();
+    assert_eq!(value["a"], 1);
+}
+
+#[test]
+fn test_lines() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\r\nsecond\n\nfourth").unwrap();
+    let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    let lines = input.lines().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(lines, ["first", "second", "", "fourth"]);
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(8));
+    let input = parse(tmp.path().to_str().unwrap()).unwrap();
+    let mut lines = input.lines();
+    assert_eq!(lines.next().unwrap().unwrap(), "first");
+    assert!(matches!(
+        lines.next().unwrap(),
+        Err(StdinError::InputTooLarge(8))
+    ));
+}
+
+#[test]
+fn test_contents_with_opts() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, " padded \n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().trim(Trim::None));
+    assert_eq!(parse(path).unwrap().contents().unwrap(), " padded \n");
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(4));
+    assert!(matches!(
+        parse(path).unwrap().contents(),
+        Err(StdinError::InputTooLarge(4))
+    ));
+    let input: FileOrStdin = path.parse().unwrap();
+    assert_eq!(input.contents().unwrap(), " padded");
+
+    let input: FileOrStdin = path.parse().unwrap();
+    assert_eq!(input.contents_limited(9).unwrap(), " padded");
+    assert!(matches!(
+        parse(path).unwrap().contents_limited(8),
+        Err(StdinError::InputTooLarge(4))
+    ));
+}
+
+#[test]
+fn test_read_stats() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "12345678\n").unwrap();
+
+    let input: FileOrStdin<u32> = FileOrStdin::from_path(tmp.path());
+    assert_eq!(input.stats(), ReadStats::default());
+    let (value, stats) = input.contents_with_stats().unwrap();
+    assert_eq!(value, 12345678);
+    assert_eq!(stats.bytes_read, 9);
+    assert_eq!(stats.items_parsed, 1);
+    assert!(!stats.hit_limit);
+
+    let path = tmp.path().to_str().unwrap();
+    let mut input = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(4))(path).unwrap();
+    let mut line = String::new();
+    assert!(input.read_line(&mut line).is_err());
+    assert_eq!(line, "1234");
+    assert!(input.stats().hit_limit);
+    assert_eq!(input.stats().bytes_read, 4);
+}
+
+#[test]
+fn test_rate_limit() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "0123456789").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let input = FileOrStdin::<String>::with_opts(StdinOpts::new().rate_limit(40))(path).unwrap();
+    let (value, stats) = input.contents_with_stats().unwrap();
+    assert_eq!(value, "0123456789");
+    assert!(stats.duration >= Duration::from_millis(250));
+}
+
+#[cfg(feature = "blake3")]
+#[test]
+fn test_digest() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\nsecond\n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+    let expected: [u8; 32] = blake3::hash(b"first\nsecond\n").into();
+
+    let parse =
+        FileOrStdin::<String>::with_opts(StdinOpts::new().digest(super::DigestAlgorithm::Blake3));
+    let (_, stats) = parse(path).unwrap().contents_with_stats().unwrap();
+    assert_eq!(stats.digest, Some(expected));
+
+    // Reading line by line through `BufRead` hashes the same bytes
+    let mut input = parse(path).unwrap();
+    let lines = input.by_ref().lines().count();
+    assert_eq!(lines, 2);
+    assert_eq!(input.stats().digest, Some(expected));
+}
+
+#[cfg(feature = "indicatif")]
+#[test]
+fn test_with_progress_bar() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\nsecond\n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let bar = indicatif::ProgressBar::hidden();
+    let input: FileOrStdin = FileOrStdin::from_path(path);
+    assert_eq!(input.with_progress_bar(bar.clone()).lines().count(), 2);
+    assert_eq!((bar.position(), bar.length()), (13, Some(13)));
+
+    // The callback registered with `on_progress` is still called
+    let reported = Arc::new(AtomicU64::new(0));
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().on_progress({
+        let reported = reported.clone();
+        move |read, _| reported.store(read, Ordering::Relaxed)
+    }));
+    let bar = indicatif::ProgressBar::hidden();
+    parse(path)
+        .unwrap()
+        .with_progress_bar(bar.clone())
+        .contents()
+        .unwrap();
+    assert_eq!((bar.position(), reported.load(Ordering::Relaxed)), (13, 13));
+
+    // Inputs opened to be validated count the reads from there on
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().validate(|_| Ok(())));
+    let bar = indicatif::ProgressBar::hidden();
+    parse(path)
+        .unwrap()
+        .with_progress_bar(bar.clone())
+        .contents()
+        .unwrap();
+    assert_eq!(bar.position(), 13);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "\u{feff}mapped").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    // SAFETY (in this test): the temp file isn't modified while it's mapped
+    let input: FileOrStdin = path.parse().unwrap();
+    let contents = unsafe { input.mmap() }.unwrap();
+    assert!(contents.is_mapped());
+    assert_eq!(&contents[..], b"\xef\xbb\xbfmapped");
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(4));
+    assert!(matches!(
+        unsafe { parse(path).unwrap().mmap() },
+        Err(StdinError::InputTooLarge(4))
+    ));
+
+    let _session = crate::StdinSession::with_input("buffered");
+    let input: FileOrStdin = "-".parse().unwrap();
+    let contents = unsafe { input.mmap() }.unwrap();
+    assert!(!contents.is_mapped());
+    assert_eq!(&contents[..], b"buffered");
+}
+
+#[test]
+fn test_source_metadata() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\nsecond\n").unwrap();
+
+    let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    assert_eq!(input.path(), Some(tmp.path()));
+    assert_eq!(input.len_hint(), Some(13));
+
+    let input: FileOrStdin = "-".parse().unwrap();
+    assert!(input.is_stdin());
+    assert_eq!(input.path(), None);
+    assert_eq!(input.len_hint(), None);
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().literal_fallback());
+    let input = parse("inline value").unwrap();
+    assert_eq!(input.path(), None);
+    assert_eq!(input.len_hint(), Some(12));
+}
+
+#[test]
+fn test_lines_numbered() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\nsecond\n").unwrap();
+
+    let input: FileOrStdin = FileOrStdin::from_path(tmp.path());
+    let lines = input
+        .lines_numbered()
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(lines, [(1, "first".into()), (2, "second".into())]);
+}
+
+#[test]
+fn test_literal_fallback() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "from file").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().literal_fallback());
+    let input = parse(path).unwrap();
+    assert!(input.is_file());
+    assert_eq!(input.contents().unwrap(), "from file");
+
+    let input = parse("inline value").unwrap();
+    assert!(!input.is_file());
+    assert_eq!(input.source_kind(), SourceKind::Literal);
+    assert_eq!(input.clone().contents().unwrap(), "inline value");
+
+    // Without the policy, a missing file is an error
+    let input: FileOrStdin = "inline value".parse().unwrap();
+    assert!(input.contents().is_err());
+}
+
+#[cfg(all(test, feature = "tokio"))]
+#[tokio::test]
+async fn test_contents_async() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "42\n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let input: FileOrStdin<u32> = path.parse().unwrap();
+    assert_eq!(input.contents_async().await.unwrap(), 42);
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().literal_fallback());
+    let input = parse("inline value").unwrap();
+    assert_eq!(input.contents_async().await.unwrap(), "inline value");
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(2));
+    assert!(matches!(
+        parse(path).unwrap().contents_async().await,
+        Err(StdinError::InputTooLarge(2))
+    ));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_contents_futures() {
+    use futures_lite::future::block_on;
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "42\n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let input: FileOrStdin<u32> = path.parse().unwrap();
+    assert_eq!(block_on(input.contents_futures()).unwrap(), 42);
+
+    let _session = crate::StdinSession::with_input("7\n");
+    let input: FileOrStdin<u32> = "-".parse().unwrap();
+    assert_eq!(block_on(input.contents_futures()).unwrap(), 7);
+
+    let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().max_bytes(2));
+    assert!(matches!(
+        block_on(parse(path).unwrap().contents_futures()),
+        Err(StdinError::InputTooLarge(2))
+    ));
+}
+
+#[test]
+fn test_debug_output() {
+    let val: FileOrStdin<u32> = "-".parse().unwrap();
+    assert_eq!(
+        format!("{val:?}"),
+        "FileOrStdin { source: Stdin, consumed: false }"
+    );
+
+    let val: FileOrStdin<u32> = FileOrStdin::from_path("input.txt");
+    assert_eq!(
+        format!("{val:?}"),
+        r#"FileOrStdin { source: File("input.txt"), consumed: false }"#
+    );
+
+    let val: FileOrStdin<u32> = "fd:3".parse().unwrap();
+    assert_eq!(
+        format!("{val:?}"),
+        "FileOrStdin { source: Fd(3), consumed: false }"
+    );
+
+    let mut val =
+        FileOrStdin::<String>::with_opts(StdinOpts::new().literal_fallback())("FILE").unwrap();
+    let mut buf = [0; 2];
+    io::Read::read_exact(&mut val, &mut buf).unwrap();
+    assert_eq!(
+        format!("{val:?}"),
+        r#"FileOrStdin { source: Literal("FILE"), consumed: true }"#
+    );
+}
+
+#[test]
+fn test_validate() {
+    let opts = StdinOpts::new().validate(|s| match s.trim().is_empty() {
+        true => Err("expected a non-empty input".to_owned()),
+        false => Ok(()),
+    });
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, " \n").unwrap();
+    let path = tmp.path().to_str().unwrap();
+    let error = FileOrStdin::<String>::with_opts(opts.clone())(path).unwrap_err();
+    assert!(matches!(error, StdinError::Invalid(e) if e == "expected a non-empty input"));
+
+    std::fs::write(&tmp, "contents\n").unwrap();
+    let input = FileOrStdin::<String>::with_opts(opts.clone())(path).unwrap();
+    assert_eq!(input.contents().unwrap(), "contents");
+
+    // Decoded with the configured policy
+    std::fs::write(&tmp, b"caf\xe9\n").unwrap();
+    let error = FileOrStdin::<String>::with_opts(opts.clone())(path).unwrap_err();
+    assert!(matches!(error, StdinError::StdIn(_)), "{error}");
+    let opts = opts.utf8(crate::Utf8Policy::Lossy);
+    let input = FileOrStdin::<String>::with_opts(opts)(path).unwrap();
+    assert_eq!(input.contents().unwrap(), "caf\u{fffd}");
+}
+
+#[test]
+fn test_share_stdin() {
+    let _session = crate::StdinSession::with_input("shared");
+    let input = FileOrStdin::<String>::with_opts(StdinOpts::new().share_stdin())("-").unwrap();
+    let clone = input.clone();
+    assert_eq!(input.contents().unwrap(), "shared");
+    assert_eq!(clone.contents().unwrap(), "shared");
+
+    let _session = crate::StdinSession::with_input("streamed");
+    let input = FileOrStdin::<String>::from_str("-").unwrap();
+    let clone = input.clone();
+    assert_eq!(input.contents().unwrap(), "streamed");
+    assert!(matches!(
+        clone.contents(),
+        Err(StdinError::StdInRepeatedUse)
+    ));
 }
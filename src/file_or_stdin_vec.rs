@@ -0,0 +1,144 @@
+use std::str::FromStr;
+
+use super::{FileOrStdin, SourceKind, StdinError, StdinOpts, Trim};
+
+/// Wrapper struct to parse the lines of a file, or of `stdin`, into a list of values
+///
+/// Unlike [`MaybeStdinVec`](crate::MaybeStdinVec), the arg value is a path to a list file
+/// (or `-`), never the list itself. Each line is trimmed and parsed with `FromStr`, while blank
+/// lines are skipped.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::FileOrStdinVec;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     hosts: FileOrStdinVec<String>,
+/// }
+///
+/// let args = Args::parse();
+/// for host in args.hosts.iter() {
+///     println!("host={host}");
+/// }
+/// ```
+///
+/// ```sh
+/// $ ./example --hosts hosts.txt
+/// $ cat hosts.txt | ./example --hosts -
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileOrStdinVec<T> {
+    inner: Vec<T>,
+    source: SourceKind,
+}
+
+impl<T> FileOrStdinVec<T> {
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let input = FileOrStdin::<String>::with_opts(opts.clone())(s)?;
+        let source = input.source_kind();
+        let mut inner = Vec::new();
+        for line in input.lines_numbered() {
+            let (n, line) = line.map_err(StdinError::from_io)?;
+            let line = opts.trim_or(Trim::Whitespace).apply(&line);
+            if line.is_empty() {
+                continue;
+            }
+            let item =
+                T::from_str(line).map_err(|e| StdinError::FromStr(format!("line {n}: {e}")))?;
+            inner.push(item);
+        }
+        debug_log!(
+            "{}: read {} items from {}",
+            std::any::type_name::<Self>(),
+            inner.len(),
+            source.name()
+        );
+        Ok(Self { inner, source })
+    }
+
+    /// A `value_parser` that reads the lines as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// Where these values were read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these values read from stdin (shorthand for checking [`FileOrStdinVec::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the values from the wrapper
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T> FromStr for FileOrStdinVec<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+impl<T> std::ops::Deref for FileOrStdinVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for FileOrStdinVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> IntoIterator for FileOrStdinVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+#[test]
+fn test_file_or_stdin_vec() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "1\n 2 \n\n3\n").unwrap();
+    let values: FileOrStdinVec<u32> = tmp.path().to_str().unwrap().parse().unwrap();
+    assert_eq!(values.source_kind(), SourceKind::File(tmp.path().into()));
+    assert_eq!(*values, [1, 2, 3]);
+
+    let _session = crate::StdinSession::with_input("4\r\n5\n");
+    let values: FileOrStdinVec<u32> = "-".parse().unwrap();
+    assert!(values.is_stdin());
+    assert_eq!(values.into_inner(), [4, 5]);
+
+    let _session = crate::StdinSession::with_input("6\nseven\n");
+    let error = "-".parse::<FileOrStdinVec<u32>>().unwrap_err();
+    assert!(matches!(error, StdinError::FromStr(e) if e.starts_with("line 2:")));
+}
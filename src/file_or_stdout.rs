@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::{Source, StdinError, StdinOpts};
+
+/// Wrapper struct for output args: either a file to write, or `stdout` for `-`
+///
+/// The output counterpart of [`FileOrStdin`](crate::FileOrStdin), so input and output args can be
+/// declared the same way.
+#[cfg_attr(feature = "file", doc = "```rust,no_run")]
+#[cfg_attr(not(feature = "file"), doc = "```ignore")]
+/// use std::io::Write;
+/// use clap::Parser;
+/// use clap_stdin::{FileOrStdin, FileOrStdout};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin,
+///     #[arg(short, long, default_value = "-")]
+///     output: FileOrStdout,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let contents: String = args.input.contents()?;
+/// args.output.writer()?.write_all(contents.to_uppercase().as_bytes())?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ ./example input.txt -o output.txt
+/// $ cat input.txt | ./example - -o -
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileOrStdout {
+    path: Option<PathBuf>,
+}
+
+impl FileOrStdout {
+    /// Write to the file at `path`
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Write to `stdout`
+    pub fn stdout() -> Self {
+        Self { path: None }
+    }
+
+    /// Is the output `stdout`
+    pub fn is_stdout(&self) -> bool {
+        self.path.is_none()
+    }
+
+    /// Is the output a file
+    pub fn is_file(&self) -> bool {
+        !self.is_stdout()
+    }
+
+    /// The path of the output file, or `None` for `stdout`
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// A `value_parser` that writes to `stdout` for the sentinel configured in `opts`
+    /// (E.g. `@-`), instead of `-`
+    ///
+    /// Only the sentinel applies to output.
+    ///
+    /// # Panics
+    ///
+    /// If `opts` sets [`StdinOpts::max_bytes`], [`StdinOpts::timeout`] or [`StdinOpts::prompt`],
+    /// which only apply to input
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        assert!(
+            opts.max_bytes.is_none() && opts.timeout.is_none() && opts.prompt.is_none(),
+            "FileOrStdout::with_opts: max_bytes, timeout and prompt only apply to input"
+        );
+        move |s| Ok(Self::from_opts(s, &opts))
+    }
+
+    fn from_opts(s: &str, opts: &StdinOpts) -> Self {
+        match opts.source(s) {
+            Source::Stdin(_) | Source::SharedStdin(_) => Self::stdout(),
+            Source::Arg(path) => Self::from_path(path),
+            Source::Path(path) => Self::from_path(path),
+            // Descriptors are only read, so `fd:N` stays a file name for output
+            Source::Fd(fd) => Self::from_path(format!("fd:{fd}")),
+        }
+    }
+
+    /// Open the output for writing, creating (or truncating) the file
+    ///
+    /// Output to a file is buffered: flush the writer to observe write errors.
+    pub fn writer(&self) -> Result<Box<dyn Write + Send>, StdinError> {
+        Ok(match &self.path {
+            Some(path) => Box::new(io::BufWriter::new(std::fs::File::create(path)?)),
+            None => Box::new(io::stdout()),
+        })
+    }
+}
+
+/// `-` writes to `stdout`, while the escaped `\-` is a file named `-`
+impl FromStr for FileOrStdout {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_opts(s, &StdinOpts::default()))
+    }
+}
+
+/// The output path, or `-` for `stdout`
+impl std::fmt::Display for FileOrStdout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => path.display().fmt(f),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+#[test]
+fn test_file_or_stdout() {
+    let output: FileOrStdout = "-".parse().unwrap();
+    assert!(output.is_stdout());
+    assert_eq!(output.path(), None);
+    assert_eq!(output.to_string(), "-");
+
+    let output: FileOrStdout = "\\-".parse().unwrap();
+    assert_eq!(output.path(), Some(Path::new("-")));
+
+    let parse = FileOrStdout::with_opts(StdinOpts::new().sentinel("@-"));
+    assert!(parse("@-").unwrap().is_stdout());
+    assert_eq!(parse("-").unwrap().path(), Some(Path::new("-")));
+
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let output: FileOrStdout = tmp.path().to_str().unwrap().parse().unwrap();
+    assert!(output.is_file());
+    let mut writer = output.writer().unwrap();
+    writer.write_all(b"written").unwrap();
+    writer.flush().unwrap();
+    assert_eq!(std::fs::read_to_string(tmp.path()).unwrap(), "written");
+}
+
+#[test]
+#[should_panic(expected = "only apply to input")]
+fn test_with_opts_rejects_input_opts() {
+    let _ = FileOrStdout::with_opts(StdinOpts::new().max_bytes(1024));
+}
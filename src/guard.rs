@@ -0,0 +1,170 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::session::{stdin_is_cached, stdin_is_split};
+use super::StdinError;
+
+static STDIN_HAS_BEEN_READ: AtomicBool = AtomicBool::new(false);
+
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+static TERMINAL_IN_USE: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Consumed-state of the innermost [`StdinGuard`] on this thread, if any
+    static SCOPED_STDIN_HAS_BEEN_READ: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Mark `stdin` as read by `consumer`, failing if it was already read
+#[cfg_attr(not(feature = "testing"), allow(unused_variables))]
+pub(crate) fn claim_stdin(consumer: &str) -> Result<(), StdinError> {
+    let already_read = SCOPED_STDIN_HAS_BEEN_READ.with(|scoped| match scoped.get() {
+        Some(read) => {
+            scoped.set(Some(true));
+            read
+        }
+        None => STDIN_HAS_BEEN_READ.swap(true, Ordering::SeqCst),
+    });
+    if already_read && !stdin_is_cached() && !stdin_is_split() {
+        return Err(StdinError::StdInRepeatedUse);
+    }
+    #[cfg(feature = "testing")]
+    report_provenance(crate::testing::PROVENANCE_PREFIX, consumer);
+    Ok(())
+}
+
+/// Report how `consumer` resolved its input on `stderr`, when the
+/// [`PROVENANCE_ENV`](crate::testing::PROVENANCE_ENV) environment variable is set
+#[cfg(feature = "testing")]
+pub(crate) fn report_provenance(prefix: &str, consumer: &str) {
+    if std::env::var_os(crate::testing::PROVENANCE_ENV).is_some() {
+        eprintln!("{prefix}{consumer}");
+    }
+}
+
+/// Mark `stdin` as unconsumed again: process-wide, and in the current thread's [`StdinGuard`] scope
+#[cfg(feature = "testing")]
+pub(crate) fn reset_stdin_read() {
+    STDIN_HAS_BEEN_READ.store(false, Ordering::SeqCst);
+    SCOPED_STDIN_HAS_BEEN_READ.with(|scoped| {
+        if scoped.get().is_some() {
+            scoped.set(Some(false));
+        }
+    });
+}
+
+/// Fail if `stdin` was already consumed, without claiming it
+pub(crate) fn ensure_stdin_unclaimed() -> Result<(), StdinError> {
+    let already_read = SCOPED_STDIN_HAS_BEEN_READ.with(|scoped| {
+        scoped
+            .get()
+            .unwrap_or_else(|| STDIN_HAS_BEEN_READ.load(Ordering::SeqCst))
+    });
+    if already_read {
+        return Err(StdinError::StdInRepeatedUse);
+    }
+    Ok(())
+}
+
+/// Exclusive use of the controlling terminal by one interactive prompt, released on drop
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+#[derive(Debug)]
+pub(crate) struct TerminalClaim(());
+
+/// Claim the terminal for interactive input, failing if another prompt is using it
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+pub(crate) fn claim_terminal() -> Result<TerminalClaim, StdinError> {
+    if TERMINAL_IN_USE.swap(true, Ordering::SeqCst) {
+        return Err(StdinError::TerminalBusy);
+    }
+    Ok(TerminalClaim(()))
+}
+
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+impl Drop for TerminalClaim {
+    fn drop(&mut self) {
+        TERMINAL_IN_USE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Scoped handle over the "stdin has been read" state for the current thread
+///
+/// By default, `stdin` may only be consumed once per process. While a `StdinGuard` is alive,
+/// reads on the creating thread are tracked by the guard instead, starting out unconsumed.
+/// This keeps tests deterministic when the test harness runs them in parallel threads:
+/// a test that reads stdin no longer affects the outcome of unrelated tests.
+///
+/// Guards can be nested; dropping a guard restores the state of the enclosing scope.
+///
+/// ```rust
+/// use clap_stdin::StdinGuard;
+///
+/// let guard = StdinGuard::scoped();
+/// assert!(!guard.is_consumed());
+/// ```
+#[derive(Debug)]
+pub struct StdinGuard {
+    previous: Option<bool>,
+    // The guard applies to the creating thread only
+    _not_send: PhantomData<*const ()>,
+}
+
+impl StdinGuard {
+    /// Start a fresh, unconsumed `stdin` scope for the current thread
+    pub fn scoped() -> Self {
+        let previous = SCOPED_STDIN_HAS_BEEN_READ.with(|scoped| scoped.replace(Some(false)));
+        Self {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Has `stdin` been consumed within this scope
+    pub fn is_consumed(&self) -> bool {
+        SCOPED_STDIN_HAS_BEEN_READ.with(|scoped| scoped.get().unwrap_or_default())
+    }
+}
+
+impl Drop for StdinGuard {
+    fn drop(&mut self) {
+        SCOPED_STDIN_HAS_BEEN_READ.with(|scoped| scoped.set(self.previous));
+    }
+}
+
+#[test]
+fn test_scoped_guard() {
+    let outer = StdinGuard::scoped();
+    assert!(claim_stdin("outer").is_ok());
+    assert!(outer.is_consumed());
+    assert!(matches!(
+        claim_stdin("outer"),
+        Err(StdinError::StdInRepeatedUse)
+    ));
+
+    {
+        let inner = StdinGuard::scoped();
+        assert!(!inner.is_consumed());
+        assert!(claim_stdin("inner").is_ok());
+    }
+    assert!(outer.is_consumed());
+    assert!(ensure_stdin_unclaimed().is_err());
+
+    // Other threads are unaffected by this thread's scope
+    std::thread::spawn(|| {
+        let guard = StdinGuard::scoped();
+        assert!(!guard.is_consumed());
+        assert!(ensure_stdin_unclaimed().is_ok());
+        assert!(claim_stdin("thread").is_ok());
+    })
+    .join()
+    .unwrap();
+}
+
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+#[test]
+fn test_claim_terminal() {
+    let claim = claim_terminal().unwrap();
+    assert!(matches!(claim_terminal(), Err(StdinError::TerminalBusy)));
+    drop(claim);
+    assert!(claim_terminal().is_ok());
+}
@@ -0,0 +1,133 @@
+//! [`schemars::JsonSchema`] implementations for the wrapper types
+//!
+//! The value wrappers are transparent: their schema is the schema of the wrapped value,
+//! so generated schemas are unchanged when a field adopts one of them.
+//! [`FileOrStdin`] is described by its argument value, a file path (or `-`).
+use std::borrow::Cow;
+#[cfg(feature = "file")]
+use std::path::PathBuf;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+#[cfg(feature = "file")]
+use crate::FileOrStdin;
+#[cfg(feature = "maybe-stdin")]
+use crate::MaybeStdin;
+#[cfg(feature = "from-source")]
+use crate::MaybeStdinFromSource;
+#[cfg(feature = "vec")]
+use crate::{MaybeStdinVec, NulStdinVec};
+
+#[cfg(feature = "maybe-stdin")]
+impl<T: JsonSchema> JsonSchema for MaybeStdin<T> {
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        T::json_schema(generator)
+    }
+}
+
+#[cfg(feature = "from-source")]
+impl<T: JsonSchema> JsonSchema for MaybeStdinFromSource<T> {
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        T::json_schema(generator)
+    }
+}
+
+#[cfg(feature = "vec")]
+impl<T: JsonSchema, const D: char, const STDIN_DELIMITED: bool> JsonSchema
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+{
+    fn inline_schema() -> bool {
+        Vec::<T>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        Vec::<T>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Vec::<T>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        Vec::<T>::json_schema(generator)
+    }
+}
+
+#[cfg(feature = "vec")]
+impl<T: JsonSchema> JsonSchema for NulStdinVec<T> {
+    fn inline_schema() -> bool {
+        Vec::<T>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        Vec::<T>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Vec::<T>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        Vec::<T>::json_schema(generator)
+    }
+}
+
+#[cfg(feature = "file")]
+impl<T> JsonSchema for FileOrStdin<T> {
+    fn inline_schema() -> bool {
+        PathBuf::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        PathBuf::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        PathBuf::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        PathBuf::json_schema(generator)
+    }
+}
+
+#[cfg(all(feature = "maybe-stdin", feature = "vec", feature = "file"))]
+#[test]
+fn test_transparent_schema() {
+    assert_eq!(
+        schemars::schema_for!(MaybeStdin<u32>),
+        schemars::schema_for!(u32)
+    );
+    assert_eq!(
+        schemars::schema_for!(MaybeStdinVec<String>),
+        schemars::schema_for!(Vec<String>)
+    );
+    assert_eq!(
+        schemars::schema_for!(FileOrStdin<u32>),
+        schemars::schema_for!(PathBuf)
+    );
+}
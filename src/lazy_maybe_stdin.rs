@@ -0,0 +1,157 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use super::{MaybeStdin, Source, SourceKind, StdinError, StdinOpts, Trim};
+
+/// Like [`MaybeStdin`], but only reads `stdin` when the value is first accessed
+///
+/// [`MaybeStdin`] reads `stdin` while the args are parsed, so argument validation (and errors
+/// reported for other args) wait for the pipe to close. `LazyMaybeStdin` only records the
+/// [`Source`] when parsing, and reads and parses it on the first call to [`LazyMaybeStdin::get`]
+/// or [`LazyMaybeStdin::into_inner`].
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::LazyMaybeStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     value: LazyMaybeStdin<u32>,
+///     #[arg(long)]
+///     dry_run: bool,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// if !args.dry_run {
+///     println!("value={}", args.value.get()?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LazyMaybeStdin<T> {
+    source: Source,
+    opts: StdinOpts,
+    value: OnceLock<T>,
+}
+
+impl<T> LazyMaybeStdin<T> {
+    /// A `value_parser` that reads and parses the value as configured by `opts`, once accessed
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Ok(Self::from_opts(s, opts.clone()))
+    }
+
+    fn from_opts(s: &str, opts: StdinOpts) -> Self {
+        Self {
+            source: opts.source(s),
+            opts,
+            value: OnceLock::new(),
+        }
+    }
+
+    /// Where this value originates from (known without reading it)
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.source_kind()
+    }
+
+    /// Is this value read from stdin (shorthand for checking [`LazyMaybeStdin::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source.is_stdin()
+    }
+
+    /// Has the value been read (and parsed) already
+    pub fn is_read(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    fn read(&self) -> Result<T, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let value = self
+            .source
+            .clone()
+            .get_value_with(std::any::type_name::<Self>(), &self.opts)?;
+        T::from_str(self.opts.trim_or(Trim::Whitespace).apply(&value))
+            .map_err(|e| StdinError::FromStr(format!("{e}")))
+    }
+
+    /// The value, reading and parsing it on the first call
+    pub fn get(&self) -> Result<&T, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+        let value = self.read()?;
+        Ok(self.value.get_or_init(|| value))
+    }
+
+    /// Extract the value, reading and parsing it if it wasn't accessed yet
+    pub fn into_inner(mut self) -> Result<T, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        match self.value.take() {
+            Some(value) => Ok(value),
+            None => self.read(),
+        }
+    }
+
+    /// Read the value into an eager [`MaybeStdin`]
+    pub fn into_maybe_stdin(self) -> Result<MaybeStdin<T>, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let kind = self.source_kind();
+        self.into_inner()
+            .map(|value| MaybeStdin::from_value(value, kind))
+    }
+}
+
+impl<T> FromStr for LazyMaybeStdin<T> {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_opts(s, StdinOpts::default()))
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for LazyMaybeStdin<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyMaybeStdin")
+            .field("source", &self.source)
+            .field("value", &self.value.get())
+            .finish()
+    }
+}
+
+#[test]
+fn test_lazy_maybe_stdin() {
+    let session = crate::StdinSession::with_input("42\n");
+    let value: LazyMaybeStdin<u32> = "-".parse().unwrap();
+    assert!(value.is_stdin());
+    assert!(!value.is_read());
+    assert!(!session.is_consumed());
+    assert_eq!(*value.get().unwrap(), 42);
+    assert!(value.is_read());
+    assert!(session.is_consumed());
+    // The value is read once and cached
+    assert_eq!(value.clone().into_inner().unwrap(), 42);
+
+    let value: LazyMaybeStdin<u32> = "7".parse().unwrap();
+    assert_eq!(value.source_kind(), SourceKind::Literal);
+    assert_eq!(*value.into_maybe_stdin().unwrap(), 7);
+
+    let value: LazyMaybeStdin<u32> = "nope".parse().unwrap();
+    assert!(matches!(value.get(), Err(StdinError::FromStr(_))));
+}
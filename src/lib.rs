@@ -1,86 +1,892 @@
-#![doc = include_str!("../README.md")]
+// The examples of the README use the wrapper types of every default feature
+#![cfg_attr(
+    all(
+        feature = "maybe-stdin",
+        feature = "from-source",
+        feature = "vec",
+        feature = "file",
+        feature = "output"
+    ),
+    doc = include_str!("../README.md")
+)]
+#![cfg_attr(
+    not(all(
+        feature = "maybe-stdin",
+        feature = "from-source",
+        feature = "vec",
+        feature = "file",
+        feature = "output"
+    )),
+    doc = "Wrapper types for [`clap`](https://docs.rs/clap) `Arg`s that help for cases where values \
+           may be passed in via `stdin`, see the README for examples with the default features"
+)]
 
-use std::io::{self, BufRead, Read, StdinLock};
+use std::io::{self, BufRead, Read};
 use std::str::FromStr;
-use std::sync::atomic::AtomicBool;
+
+/// Emit a debug-level log record (with the `log` feature) about how a source was resolved
+#[cfg(feature = "log")]
+#[allow(unused_macros)]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        log::debug!(target: "clap_stdin", $($arg)*)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+#[allow(unused_macros)]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "maybe-stdin")]
 mod maybe_stdin;
+#[cfg(feature = "maybe-stdin")]
 pub use maybe_stdin::MaybeStdin;
+
+#[cfg(all(feature = "maybe-stdin", feature = "clap"))]
+mod auto_stdin;
+#[cfg(all(feature = "maybe-stdin", feature = "clap"))]
+pub use auto_stdin::{AutoStdin, AutoStdinArg, InputArg};
+
+#[cfg(feature = "maybe-stdin")]
+mod maybe_stdin_bytes;
+#[cfg(feature = "maybe-stdin")]
+pub use maybe_stdin_bytes::MaybeStdinBytes;
+
+#[cfg(feature = "maybe-stdin")]
+mod maybe_stdin_hex;
+#[cfg(feature = "maybe-stdin")]
+pub use maybe_stdin_hex::MaybeStdinHex;
+
+#[cfg(feature = "maybe-stdin")]
+mod maybe_stdin_os;
+#[cfg(feature = "maybe-stdin")]
+pub use maybe_stdin_os::MaybeStdinOs;
+
+#[cfg(feature = "maybe-stdin")]
+mod lazy_maybe_stdin;
+#[cfg(feature = "maybe-stdin")]
+pub use lazy_maybe_stdin::LazyMaybeStdin;
+
+#[cfg(feature = "from-source")]
 mod maybe_stdin_from_source;
-pub use maybe_stdin_from_source::FromSource;
-pub use maybe_stdin_from_source::MaybeStdinFromSource;
-pub use maybe_stdin_from_source::MaybeStdinVec;
+#[cfg(feature = "derive")]
+pub use clap_stdin_derive::FromSource;
+#[cfg(feature = "from-source")]
+pub use maybe_stdin_from_source::{FromSource, MaybeStdinFromSource};
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod derive;
+
+#[cfg(feature = "vec")]
+mod maybe_stdin_vec;
+#[cfg(feature = "vec")]
+pub use maybe_stdin_vec::{DelimitedStdinVec, MaybeStdinVec};
+#[cfg(feature = "vec")]
+mod nul_stdin_vec;
+#[cfg(feature = "vec")]
+pub use nul_stdin_vec::NulStdinVec;
+#[cfg(feature = "vec")]
+mod maybe_stdin_iter;
+#[cfg(feature = "vec")]
+pub use maybe_stdin_iter::{MaybeStdinItems, MaybeStdinIter};
+#[cfg(feature = "vec")]
+mod maybe_stdin_map;
+#[cfg(feature = "vec")]
+pub use maybe_stdin_map::MaybeStdinMap;
 
+#[cfg(feature = "serde")]
+mod maybe_stdin_array;
+#[cfg(feature = "serde")]
+pub use maybe_stdin_array::MaybeStdinArray;
+
+#[cfg(feature = "base64")]
+mod maybe_stdin_base64;
+#[cfg(feature = "base64")]
+pub use maybe_stdin_base64::MaybeStdinBase64;
+
+#[cfg(feature = "secret")]
+mod maybe_stdin_secret;
+#[cfg(feature = "secret")]
+pub use maybe_stdin_secret::MaybeStdinSecret;
+
+#[cfg(feature = "file")]
 mod file_or_stdin;
+#[cfg(feature = "file")]
 pub use file_or_stdin::FileOrStdin;
+#[cfg(feature = "file")]
+mod stdin_or_file_lines;
+#[cfg(feature = "file")]
+pub use stdin_or_file_lines::{StdinLines, StdinOrFileLines};
+#[cfg(feature = "file")]
+mod file_or_stdin_vec;
+#[cfg(feature = "file")]
+pub use file_or_stdin_vec::FileOrStdinVec;
+#[cfg(feature = "file")]
+mod paths_from_stdin;
+#[cfg(feature = "file")]
+pub use paths_from_stdin::PathsFromStdin;
+
+#[cfg(all(feature = "serde", feature = "file"))]
+mod structured;
+#[cfg(all(feature = "toml", feature = "file"))]
+pub use structured::Toml;
+#[cfg(all(feature = "yaml", feature = "file"))]
+pub use structured::Yaml;
+#[cfg(all(feature = "serde", feature = "file"))]
+pub use structured::{Json, JsonLines};
+
+#[cfg(feature = "csv")]
+mod csv_input;
+#[cfg(feature = "csv")]
+pub use csv_input::Csv;
+
+#[cfg(feature = "output")]
+mod file_or_stdout;
+#[cfg(feature = "output")]
+pub use file_or_stdout::FileOrStdout;
+
+#[cfg(feature = "file")]
+mod path_or_stdin;
+#[cfg(feature = "file")]
+pub use path_or_stdin::PathOrStdin;
+
+#[cfg(feature = "maybe-stdin")]
+mod fallback;
+#[cfg(feature = "maybe-stdin")]
+pub use fallback::Fallback;
+
+#[cfg(feature = "arrow")]
+mod arrow_input;
+#[cfg(feature = "arrow")]
+pub use arrow_input::{ArrowInput, RecordBatches};
+
+mod content_source;
+pub use content_source::ContentSource;
+
+mod sections;
+pub use sections::Sections;
+
+mod opts;
+pub use opts::{StdinOpts, Trim, Utf8Policy};
 
-static STDIN_HAS_BEEN_READ: AtomicBool = AtomicBool::new(false);
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+mod decompress;
 
+mod fd;
+use fd::{open_fd, parse_fd};
+
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "mmap")]
+pub use mmap::MappedContents;
+
+#[cfg(feature = "encoding")]
+mod transcode;
+#[cfg(feature = "encoding")]
+pub use encoding_rs::Encoding;
+
+#[cfg(any(feature = "sha2", feature = "blake3"))]
+mod digest;
+#[cfg(any(feature = "sha2", feature = "blake3"))]
+pub use digest::DigestAlgorithm;
+
+#[cfg(feature = "clap")]
+mod completion;
+#[cfg(feature = "miette")]
+mod diagnostic;
+mod progress;
+#[cfg(feature = "clap")]
+pub use completion::{complete_file_or_stdin, StdinArgExt};
+#[cfg(feature = "clap")]
+mod value_parser;
+#[cfg(feature = "clap")]
+pub use value_parser::StdinValueParser;
+mod retry;
+use retry::RetryInterrupted;
+
+mod bom;
+use bom::SkipBom;
+
+mod records;
+pub use records::Records;
+
+mod stats;
+pub use stats::ReadStats;
+
+mod guard;
+mod session;
+mod shared_stdin;
+mod terminal;
+pub use guard::StdinGuard;
+use guard::{claim_stdin, ensure_stdin_unclaimed};
+pub use session::StdinSession;
+use session::{stdin_reader, with_stdin};
+pub use shared_stdin::SharedStdin;
+#[cfg(feature = "crossterm")]
+pub use terminal::RawTerminal;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(all(
+    feature = "schemars",
+    any(
+        feature = "maybe-stdin",
+        feature = "from-source",
+        feature = "vec",
+        feature = "file"
+    )
+))]
+mod json_schema;
+
+/// Reader over the contents of a [`Source`]
+pub type BoxedReader = Box<dyn BufRead + Send + Sync + 'static>;
+
+/// Buffer size used when streaming input
+const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Environment variable overriding [`StdinOpts::max_bytes`] of every arg, so operators can harden
+/// deployed binaries without rebuilding them. Read when the input is read; invalid values (E.g.
+/// `10MB`) fail with [`StdinError::EnvNotNumber`].
+pub const MAX_BYTES_ENV: &str = "CLAP_STDIN_MAX_BYTES";
+
+/// Environment variable overriding [`StdinOpts::rate_limit`] of every arg (in bytes per second).
+/// Read when args are parsed; invalid values fail with [`StdinError::EnvNotNumber`].
+pub const RATE_LIMIT_ENV: &str = "CLAP_STDIN_RATE_LIMIT";
+
+/// Environment variable overriding [`StdinOpts::timeout`] of every arg (in seconds), E.g. so CI
+/// jobs fail instead of hanging on a missing pipe. Invalid values fail with
+/// [`StdinError::EnvNotNumber`].
+pub const TIMEOUT_ENV: &str = "CLAP_STDIN_TIMEOUT";
+
+/// Errors from reading args and `stdin`
+///
+/// Some variants only exist with the features that produce them, so the enum is non-exhaustive:
+/// enabling a feature (in any crate of the build) must not break matches on it.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum StdinError {
     #[error("stdin read from more than once")]
     StdInRepeatedUse,
+    #[error("no input on stdin for '-' (did you forget to pipe data in?)")]
+    StdinClosed,
+    #[error("expected piped input for '-', but stdin is a terminal (pipe data in, or pass a value instead)")]
+    StdinIsTty,
+    #[error("no input on stdin within {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("input exceeds the maximum size of {0} bytes")]
+    InputTooLarge(u64),
+    #[error("environment variable {0} is not set")]
+    EnvNotSet(String),
+    #[error("environment variable {0} is not valid UTF-8")]
+    EnvNotUnicode(String),
+    #[error("environment variable {0} is not a valid number: {1:?}")]
+    EnvNotNumber(String, String),
+    #[cfg(feature = "http")]
+    #[error("fetching {0} failed with HTTP status {1}")]
+    HttpStatus(String, u16),
+    #[cfg(feature = "http")]
+    #[error("fetching {0} failed: {1}")]
+    Http(String, String),
+    #[error("failed to {1} '{path}'", path = .0.display())]
+    File(std::path::PathBuf, &'static str, #[source] io::Error),
     #[error(transparent)]
     StdIn(#[from] io::Error),
     #[error("unable to parse from_str: {0}")]
     FromStr(String),
+    /// An arg value rejected by `FromStr`, which miette diagnostics point at
+    #[error("unable to parse from_str: {message}")]
+    InvalidArg {
+        message: String,
+        #[cfg(feature = "miette")]
+        value: miette::NamedSource<String>,
+    },
+    #[cfg(feature = "maybe-stdin")]
+    #[error("invalid hex input: {0}")]
+    Hex(String),
+    #[cfg(feature = "base64")]
+    #[error("invalid base64 input: {0}")]
+    Base64(String),
+    #[cfg(feature = "file")]
+    #[error(
+        "not found: {}",
+        .0.iter().map(|path| format!("'{}'", path.display())).collect::<Vec<_>>().join(", ")
+    )]
+    PathsNotFound(Vec<std::path::PathBuf>),
+    #[error("{0}")]
+    Invalid(String),
+    #[cfg(feature = "from-source")]
     #[error("unable to parse from_source: {0}")]
     FromSource(String),
+    #[cfg(feature = "maybe-stdin")]
+    #[error("no value provided by any source")]
+    MissingValue,
+    #[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+    #[error("interactive input required, but no terminal is available")]
+    NoTerminal,
+    #[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+    #[error("the terminal is already in use by another interactive prompt")]
+    TerminalBusy,
+    #[cfg(feature = "maybe-stdin")]
+    #[error("value was not confirmed")]
+    NotConfirmed,
+    #[cfg(feature = "serde")]
+    #[error("unable to deserialize: {0}")]
+    Deserialize(String),
+    #[cfg(feature = "arrow")]
+    #[error("unable to read columnar input: {0}")]
+    Arrow(String),
+}
+
+impl StdinError {
+    /// Wrap an I/O error of the `op` operation (E.g. `"open"`) on the file at `path`
+    #[cfg(feature = "file")]
+    pub(crate) fn file(path: &std::path::Path, op: &'static str) -> impl FnOnce(io::Error) -> Self {
+        let path = path.to_owned();
+        move |e| StdinError::File(path, op, e)
+    }
+
+    /// The arg `value` was rejected by `FromStr` with `message`
+    #[cfg(feature = "maybe-stdin")]
+    pub(crate) fn invalid_arg(value: &str, message: String) -> Self {
+        #[cfg(not(feature = "miette"))]
+        let _ = value;
+        StdinError::InvalidArg {
+            message,
+            #[cfg(feature = "miette")]
+            value: miette::NamedSource::new("argument", value.to_owned()),
+        }
+    }
+
+    /// Recover the `StdinError` carried by an [`io::Error`] (E.g. from a `Read` impl)
+    #[cfg(feature = "file")]
+    pub(crate) fn from_io(e: io::Error) -> Self {
+        match e.get_ref().is_some_and(|inner| inner.is::<StdinError>()) {
+            true => *e
+                .into_inner()
+                .and_then(|inner| inner.downcast().ok())
+                .expect("inner error is a StdinError"),
+            false => StdinError::StdIn(e),
+        }
+    }
+}
+
+impl From<StdinError> for io::Error {
+    fn from(e: StdinError) -> Self {
+        match e {
+            StdinError::StdIn(e) => e,
+            StdinError::File(path, op, e) => {
+                io::Error::new(e.kind(), StdinError::File(path, op, e))
+            }
+            #[cfg(feature = "file")]
+            e @ StdinError::PathsNotFound(_) => io::Error::new(io::ErrorKind::NotFound, e),
+            e => io::Error::other(e),
+        }
+    }
+}
+
+/// Read the entire contents of `reader` as text, failing if it exceeds `max_bytes`
+pub(crate) fn read_to_string_limited(
+    reader: impl Read,
+    max_bytes: Option<u64>,
+    utf8: Utf8Policy,
+) -> Result<String, StdinError> {
+    Ok(utf8.decode(read_to_end_limited(reader, max_bytes)?)?)
+}
+
+/// Read the entire contents of `reader`, failing if it exceeds `max_bytes`
+pub(crate) fn read_to_end_limited(
+    reader: impl Read,
+    max_bytes: Option<u64>,
+) -> Result<Vec<u8>, StdinError> {
+    let mut input = Vec::new();
+    match max_bytes {
+        Some(max_bytes) => {
+            reader
+                .take(max_bytes.saturating_add(1))
+                .read_to_end(&mut input)?;
+            if input.len() as u64 > max_bytes {
+                return Err(StdinError::InputTooLarge(max_bytes));
+            }
+        }
+        None => {
+            let mut reader = reader;
+            reader.read_to_end(&mut input)?;
+        }
+    }
+    Ok(input)
+}
+
+/// Read the entire contents of the async `reader`, failing if it exceeds `max_bytes`
+#[cfg(feature = "async")]
+pub(crate) async fn read_to_end_futures(
+    reader: impl futures_lite::AsyncRead + Unpin,
+    max_bytes: Option<u64>,
+) -> Result<Vec<u8>, StdinError> {
+    use futures_lite::AsyncReadExt;
+
+    let mut input = Vec::new();
+    match max_bytes {
+        Some(max_bytes) => {
+            reader
+                .take(max_bytes.saturating_add(1))
+                .read_to_end(&mut input)
+                .await?;
+            if input.len() as u64 > max_bytes {
+                return Err(StdinError::InputTooLarge(max_bytes));
+            }
+        }
+        None => {
+            let mut reader = reader;
+            reader.read_to_end(&mut input).await?;
+        }
+    }
+    Ok(input)
+}
+
+/// Async reader over `stdin` (or the input of the current [`StdinSession`]), read on a
+/// background thread
+#[cfg(feature = "async")]
+pub(crate) fn futures_stdin() -> std::pin::Pin<Box<dyn futures_lite::AsyncRead + Send>> {
+    match session::take_session_input() {
+        Some(input) => Box::pin(futures_lite::io::Cursor::new(input.into_inner())),
+        None => Box::pin(blocking::Unblock::new(io::stdin())),
+    }
+}
+
+/// Split `reader` into lines (without line endings), decoding each according to `utf8`
+pub(crate) fn decode_lines(
+    reader: impl BufRead,
+    utf8: Utf8Policy,
+) -> impl Iterator<Item = io::Result<String>> {
+    reader.split(b'\n').map(move |line| {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        utf8.decode(line)
+    })
+}
+
+/// Pair each line with its 1-based line number, as used in error messages
+pub(crate) fn number_lines(
+    lines: impl Iterator<Item = io::Result<String>>,
+) -> impl Iterator<Item = io::Result<(usize, String)>> {
+    lines
+        .enumerate()
+        .map(|(i, line)| line.map(|line| (i + 1, line)))
+}
+
+/// The text of an arg value holding a path, decoded according to `utf8`
+fn path_text(path: std::path::PathBuf, utf8: Utf8Policy) -> io::Result<String> {
+    match path.into_os_string().into_string() {
+        Ok(value) => Ok(value),
+        Err(path) => utf8.decode(path.into_encoded_bytes()),
+    }
+}
+
+/// The raw bytes as an [`OsString`](std::ffi::OsString): any bytes on Unix, valid UTF-8 elsewhere
+#[cfg(any(feature = "maybe-stdin", feature = "vec", feature = "file"))]
+fn os_string_from_bytes(bytes: Vec<u8>) -> Result<std::ffi::OsString, StdinError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Ok(std::ffi::OsString::from_vec(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        String::from_utf8(bytes)
+            .map(std::ffi::OsString::from)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+    }
 }
 
 /// Source of the value contents will be either from `stdin` or a CLI arg provided value
+///
+/// `Source` can be used directly as an arg type, for full control over when and how the
+/// value is read:
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{Source, SourceKind};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: Source,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse_from(["example", "a\nb"]);
+/// assert_eq!(args.input.source_kind(), SourceKind::Literal);
+/// let lines = args.input.lines()?.collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(lines, ["a", "b"]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The arg value is used as is, except for `-` and `fd:N` (E.g. `fd:3`), which reads the inherited
+/// file descriptor `N` on Unix. The escaped `\fd:N` is the literal value `fd:N`. To read the file
+/// at a path instead, use [`FileOrStdin`].
+///
+/// Clones of a `Source::SharedStdin` (E.g. with [`StdinOpts::share_stdin`]) can all read the
+/// contents of `stdin`.
 #[derive(Clone)]
 pub enum Source {
     Stdin(Stdin),
+    /// `stdin`, with its contents kept for the clones of the source (see [`SharedStdin`])
+    SharedStdin(SharedStdin),
     Arg(String),
+    /// An arg value holding a path, kept exactly even if it isn't valid UTF-8 (see [`Source::path`])
+    Path(std::path::PathBuf),
+    /// An inherited file descriptor, given as `fd:N`
+    Fd(u32),
+}
+
+/// Where the value of a wrapper type originated from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SourceKind {
+    /// Read from `stdin`
+    Stdin,
+    /// Provided directly as a value (E.g. a CLI arg value)
+    Literal,
+    /// Read from the file at the given path
+    File(std::path::PathBuf),
+    /// Read from the environment variable with the given name
+    Env(String),
+    /// A default value, used because no other source provided one
+    Default,
+    /// Read from the inherited file descriptor with the given number
+    Fd(u32),
+    /// Fetched from the given `http://` or `https://` URL
+    #[cfg(feature = "http")]
+    Url(String),
+}
+
+impl SourceKind {
+    /// Name of the kind, without the path, variable name or URL it holds, so debug logs never
+    /// include values that may be secret
+    #[cfg(any(
+        feature = "maybe-stdin",
+        feature = "from-source",
+        feature = "vec",
+        feature = "file"
+    ))]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            SourceKind::Stdin => "stdin",
+            SourceKind::Literal => "a literal",
+            SourceKind::File(_) => "a file",
+            SourceKind::Env(_) => "an environment variable",
+            SourceKind::Default => "the default value",
+            SourceKind::Fd(_) => "a descriptor",
+            #[cfg(feature = "http")]
+            SourceKind::Url(_) => "a URL",
+        }
+    }
 }
 
 /// Stdin source, which can be used to read from `stdin`. DO NOT read from stdin yourself, use `Stdin.read()` instead.
+///
+/// Use a [`SharedStdin`] for a `stdin` whose clones can all read its contents.
 #[derive(Debug, Clone)]
 #[repr(transparent)]
 pub struct Stdin;
 
 impl Source {
-    pub(crate) fn into_reader(self) -> Result<impl std::io::Read, StdinError> {
-        let input: Box<dyn std::io::Read + 'static> = match self {
-            Source::Stdin(_) => {
-                if STDIN_HAS_BEEN_READ.load(std::sync::atomic::Ordering::Acquire) {
-                    return Err(StdinError::StdInRepeatedUse);
+    /// Create a source holding a literal argument value
+    pub fn literal(value: impl Into<String>) -> Self {
+        Source::Arg(value.into())
+    }
+
+    /// Create a source pointing at a file path
+    ///
+    /// The path is kept as is, so paths that aren't valid UTF-8 can still be opened by
+    /// [`FileOrStdin`]. Read as text, they follow the crate default [`Utf8Policy`].
+    pub fn path(path: impl Into<std::path::PathBuf>) -> Self {
+        Source::Path(path.into())
+    }
+
+    /// Create a source that reads from `stdin`
+    pub fn stdin() -> Self {
+        Source::Stdin(Stdin)
+    }
+
+    /// The source of an arg value other than `stdin`: an `fd:N` descriptor (unless escaped as
+    /// `\fd:N`), or the value itself
+    pub(crate) fn from_arg(value: &str) -> Self {
+        match parse_fd(value) {
+            Some(fd) => Source::Fd(fd),
+            None => match value.strip_prefix('\\') {
+                Some(escaped) if escaped.starts_with("fd:") => Source::Arg(escaped.to_owned()),
+                _ => Source::Arg(value.to_owned()),
+            },
+        }
+    }
+
+    /// Is this source `stdin`
+    pub fn is_stdin(&self) -> bool {
+        matches!(self, Source::Stdin(_) | Source::SharedStdin(_))
+    }
+
+    /// The arg value, or `None` if the source is `stdin`, a descriptor (or a path that isn't
+    /// valid UTF-8)
+    pub fn as_arg_str(&self) -> Option<&str> {
+        match self {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => None,
+            Source::Arg(value) => Some(value),
+            Source::Path(path) => path.to_str(),
+        }
+    }
+
+    /// The arg value as a path, or `None` if the source is `stdin` or a descriptor
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use clap_stdin::Source;
+    ///
+    /// assert_eq!(Source::path("input.txt").as_path(), Some(Path::new("input.txt")));
+    /// assert_eq!(Source::literal("input.txt").as_path(), Some(Path::new("input.txt")));
+    /// assert_eq!(Source::stdin().as_path(), None);
+    /// ```
+    pub fn as_path(&self) -> Option<&std::path::Path> {
+        match self {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => None,
+            Source::Arg(value) => Some(value.as_ref()),
+            Source::Path(path) => Some(path),
+        }
+    }
+
+    /// Take the arg value, or `None` if the source is `stdin`, a descriptor (or a path that isn't
+    /// valid UTF-8)
+    ///
+    /// Use [`String::try_from`] to get the contents of `stdin` instead
+    pub fn into_arg_string(self) -> Option<String> {
+        match self {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => None,
+            Source::Arg(value) => Some(value),
+            Source::Path(path) => path.into_os_string().into_string().ok(),
+        }
+    }
+
+    /// Where the contents of this source come from: [`SourceKind::Stdin`], [`SourceKind::Fd`] or
+    /// [`SourceKind::Literal`]
+    pub fn source_kind(&self) -> SourceKind {
+        match self {
+            Source::Stdin(_) | Source::SharedStdin(_) => SourceKind::Stdin,
+            Source::Fd(fd) => SourceKind::Fd(*fd),
+            Source::Arg(_) | Source::Path(_) => SourceKind::Literal,
+        }
+    }
+
+    /// The arg value, or the entire contents of `stdin`
+    ///
+    /// Fails with [`StdinError::StdinClosed`] if `stdin` provides no data at all
+    pub fn contents(self) -> Result<String, StdinError> {
+        self.get_value(std::any::type_name::<Self>())
+    }
+
+    /// The arg value, or the entire contents of `stdin` read through the `futures` I/O traits,
+    /// without blocking the async runtime
+    ///
+    /// ```rust,no_run
+    /// use clap_stdin::Source;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let source: Source = "-".parse()?;
+    /// let contents = futures_lite::future::block_on(source.contents_futures())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn contents_futures(self) -> Result<String, StdinError> {
+        match self {
+            Source::Stdin(_) | Source::SharedStdin(_) => {
+                let shared = match self {
+                    Source::SharedStdin(stdin) => Some(stdin),
+                    _ => None,
+                };
+                let input = match shared.as_ref().and_then(SharedStdin::kept) {
+                    Some(contents) => contents.to_vec(),
+                    None => {
+                        claim_stdin(std::any::type_name::<Self>())?;
+                        let max_bytes = opts::max_bytes_limit(None)?;
+                        let input = read_to_end_futures(futures_stdin(), max_bytes).await?;
+                        match shared {
+                            Some(stdin) => stdin.keep(input).to_vec(),
+                            None => input,
+                        }
+                    }
+                };
+                if input.is_empty() {
+                    return Err(StdinError::StdinClosed);
                 }
-                STDIN_HAS_BEEN_READ.store(true, std::sync::atomic::Ordering::SeqCst);
-                Box::new(std::io::stdin())
+                Ok(Utf8Policy::current_default().decode(input)?)
+            }
+            Source::Fd(fd) => {
+                let input = blocking::unblock(move || {
+                    read_to_end_limited(open_fd(fd)?, opts::max_bytes_limit(None)?)
+                })
+                .await?;
+                Ok(Utf8Policy::current_default().decode(input)?)
             }
-            Source::Arg(filepath) => {
-                let f = std::fs::File::open(filepath)?;
-                Box::new(f)
+            Source::Arg(value) => Ok(value),
+            Source::Path(path) => Ok(path_text(path, Utf8Policy::current_default())?),
+        }
+    }
+
+    /// A reader over the raw bytes of the arg value, or of `stdin` or a descriptor
+    pub fn reader(self) -> Result<BoxedReader, StdinError> {
+        self.reader_with(std::any::type_name::<Self>(), None)
+    }
+
+    /// Like [`Source::reader`], preparing `stdin` as configured by `opts` and claiming it for
+    /// `consumer`
+    pub(crate) fn reader_with(
+        self,
+        consumer: &str,
+        opts: Option<&StdinOpts>,
+    ) -> Result<BoxedReader, StdinError> {
+        let reader: BoxedReader = match self {
+            Source::Stdin(stdin) => stdin.reader(consumer, opts)?,
+            Source::SharedStdin(stdin) => stdin.reader(consumer, opts)?,
+            Source::Fd(fd) => Box::new(io::BufReader::new(RetryInterrupted(open_fd(fd)?))),
+            Source::Arg(value) => Box::new(io::Cursor::new(value.into_bytes())),
+            Source::Path(path) => {
+                Box::new(io::Cursor::new(path.into_os_string().into_encoded_bytes()))
             }
         };
-        Ok(input)
+        Ok(reader)
+    }
+
+    /// Iterate over the lines of the arg value, or of `stdin` (without a leading UTF-8 byte order
+    /// mark)
+    pub fn lines(self) -> Result<impl Iterator<Item = io::Result<String>>, StdinError> {
+        let reader = SkipBom::new(self.reader()?);
+        Ok(decode_lines(reader, Utf8Policy::current_default()))
+    }
+
+    /// Get the value of the arg, or the contents of `stdin`
+    ///
+    /// Fails with [`StdinError::StdinClosed`] if `stdin` provides no data at all
+    pub(crate) fn get_value(self, consumer: &str) -> Result<String, StdinError> {
+        self.get_value_with(consumer, &StdinOpts::default())
     }
 
-    pub(crate) fn get_value(self) -> Result<String, StdinError> {
+    /// Like [`Source::get_value`], applying the limits configured in `opts`
+    pub(crate) fn get_value_with(
+        self,
+        consumer: &str,
+        opts: &StdinOpts,
+    ) -> Result<String, StdinError> {
         match self {
-            Source::Stdin(_) => {
-                if STDIN_HAS_BEEN_READ.load(std::sync::atomic::Ordering::Acquire) {
-                    return Err(StdinError::StdInRepeatedUse);
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => {
+                let is_stdin = self.is_stdin();
+                let reader = self.reader_with(consumer, Some(opts))?;
+                let max_bytes = opts::max_bytes_limit(Some(opts))?;
+                let input = read_to_string_limited(
+                    opts.text_reader(opts.progress_reader(reader, None)),
+                    max_bytes,
+                    opts.utf8_policy(),
+                )?;
+                let input = opts.normalize(input);
+                if is_stdin && input.is_empty() {
+                    return Err(StdinError::StdinClosed);
                 }
-                STDIN_HAS_BEEN_READ.store(true, std::sync::atomic::Ordering::SeqCst);
-                let stdin = io::stdin();
-                let mut input = String::new();
-                stdin.lock().read_to_string(&mut input)?;
+                opts.check(&input)?;
                 Ok(input)
             }
-            Source::Arg(value) => Ok(value),
+            Source::Arg(value) => {
+                opts.check(&value)?;
+                Ok(value)
+            }
+            Source::Path(path) => {
+                Source::Arg(path_text(path, opts.utf8_policy())?).get_value_with(consumer, opts)
+            }
+        }
+    }
+
+    pub(crate) fn get_bytes(self, consumer: &str) -> Result<Vec<u8>, StdinError> {
+        self.get_bytes_with(consumer, &StdinOpts::default())
+    }
+
+    /// Like [`Source::get_bytes`], applying the limits configured in `opts`
+    pub(crate) fn get_bytes_with(
+        self,
+        consumer: &str,
+        opts: &StdinOpts,
+    ) -> Result<Vec<u8>, StdinError> {
+        match self {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => {
+                let is_stdin = self.is_stdin();
+                let reader = self.reader_with(consumer, Some(opts))?;
+                let max_bytes = opts::max_bytes_limit(Some(opts))?;
+                let input = read_to_end_limited(opts.progress_reader(reader, None), max_bytes)?;
+                if is_stdin && input.is_empty() {
+                    return Err(StdinError::StdinClosed);
+                }
+                opts.check(&String::from_utf8_lossy(&input))?;
+                Ok(input)
+            }
+            Source::Arg(value) => {
+                opts.check(&value)?;
+                Ok(value.into_bytes())
+            }
+            Source::Path(path) => {
+                opts.check(&path.to_string_lossy())?;
+                Ok(path.into_os_string().into_encoded_bytes())
+            }
+        }
+    }
+}
+
+/// The arg value, or the contents of `stdin`
+impl TryFrom<Source> for String {
+    type Error = StdinError;
+
+    fn try_from(source: Source) -> Result<Self, Self::Error> {
+        source.get_value(std::any::type_name::<Self>())
+    }
+}
+
+/// The arg value as bytes, or the raw contents of `stdin`
+impl TryFrom<Source> for Vec<u8> {
+    type Error = StdinError;
+
+    fn try_from(source: Source) -> Result<Self, Self::Error> {
+        source.get_bytes(std::any::type_name::<Self>())
+    }
+}
+
+/// The arg value as a path, or a path read from `stdin` (with surrounding whitespace trimmed)
+impl TryFrom<Source> for std::path::PathBuf {
+    type Error = StdinError;
+
+    fn try_from(source: Source) -> Result<Self, Self::Error> {
+        if let Source::Path(path) = source {
+            return Ok(path);
         }
+        let value = source.get_value(std::any::type_name::<Self>())?;
+        Ok(Self::from(value.trim()))
     }
 }
 
+/// `-` reads from `stdin`, while the escaped `\-` is the literal value `-`
 impl FromStr for Source {
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "-" => Ok(Self::Stdin(Stdin)),
-            arg => Ok(Self::Arg(arg.to_owned())),
+            "-" => Ok(Self::stdin()),
+            "\\-" => Ok(Self::Arg(String::from("-"))),
+            arg => Ok(Self::from_arg(arg)),
         }
     }
 }
@@ -88,24 +894,306 @@ impl FromStr for Source {
 impl std::fmt::Debug for Source {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Source::Stdin(_) => write!(f, "stdin"),
-            Source::Arg(v) => v.fmt(f),
+            Source::Stdin(_) => f.write_str("Stdin"),
+            Source::SharedStdin(_) => f.write_str("SharedStdin"),
+            Source::Arg(v) => f.debug_tuple("Arg").field(v).finish(),
+            Source::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Source::Fd(fd) => f.debug_tuple("Fd").field(fd).finish(),
         }
     }
 }
 
 impl Stdin {
+    /// Keep the contents of `stdin` once read, so later reads return the same contents instead of
+    /// failing with [`StdinError::StdInRepeatedUse`]
+    ///
+    /// Call this before parsing the args. With the cache enabled, every argument given `-` (E.g.
+    /// a [`MaybeStdin`] and a [`FileOrStdin`]) receives the entire input, so it is read into
+    /// memory at once rather than streamed, up to [`MAX_BYTES_ENV`] if it is set. The cache can't
+    /// be disabled. Each [`StdinSession`] with input caches its own input, the process `stdin` is
+    /// cached once for the whole process.
+    #[cfg_attr(feature = "maybe-stdin", doc = "```rust,no_run")]
+    #[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, Stdin};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     first: MaybeStdin<String>,
+    ///     second: MaybeStdin<String>,
+    /// }
+    ///
+    /// Stdin::enable_cache();
+    /// let args = Args::parse();
+    /// assert_eq!(*args.first, *args.second);
+    /// ```
+    ///
+    /// ```sh
+    /// $ echo "value" | ./example - -
+    /// ```
+    pub fn enable_cache() {
+        session::enable_stdin_cache();
+    }
+
+    /// Split `stdin` into records on `separator` (E.g. `"\n---\n"`), handing record N to the Nth
+    /// argument reading `-`, instead of failing with [`StdinError::StdInRepeatedUse`]
+    ///
+    /// Call this before parsing the args: the first argument reading `stdin` reads it entirely
+    /// into memory, up to [`MAX_BYTES_ENV`] if it is set. Arguments receive the records in the
+    /// order they read `stdin`, which is the order clap parses their values in: the order of the
+    /// command line, not of the fields of the `Args` struct. Args falling back to a
+    /// `default_value = "-"` read `stdin` after all the args given on the command line. An
+    /// argument left without a record fails like one reading an empty `stdin`. Empty records are
+    /// skipped, like with [`Stdin::records`].
+    ///
+    /// Each [`StdinSession`] with input splits its own input, the process `stdin` is split once
+    /// for the whole process. [`Stdin::reset_split_records`] stops splitting `stdin`.
+    #[cfg_attr(feature = "maybe-stdin", doc = "```rust,no_run")]
+    #[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, Stdin};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     query: MaybeStdin<String>,
+    ///     variables: MaybeStdin<String>,
+    /// }
+    ///
+    /// Stdin::split_records("\n---\n");
+    /// let args = Args::parse();
+    /// ```
+    ///
+    /// ```sh
+    /// $ printf 'query {}\n---\n{"id": 1}' | ./example - -
+    /// ```
+    pub fn split_records(separator: impl Into<String>) {
+        session::split_stdin_records(separator.into());
+    }
+
+    /// Stop splitting `stdin` into records (see [`Stdin::split_records`]), dropping the records
+    /// not handed out yet
+    pub fn reset_split_records() {
+        session::reset_stdin_split();
+    }
+
     /// Read from stdin. Use this method to read from stdin and DO NOT read from stdin yourself.
     pub fn read_string(&self) -> Result<String, StdinError> {
-        Source::Stdin(Stdin).get_value()
+        let opts = StdinOpts::default();
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        let max_bytes = opts::max_bytes_limit(None)?;
+        let input = read_to_string_limited(reader, max_bytes, opts.utf8_policy())?;
+        Ok(opts.normalize(input))
     }
 
-    pub fn lines(&self) -> Result<io::Lines<StdinLock>, StdinError> {
-        if STDIN_HAS_BEEN_READ.load(std::sync::atomic::Ordering::Acquire) {
-            return Err(StdinError::StdInRepeatedUse);
-        };
-        STDIN_HAS_BEEN_READ.store(true, std::sync::atomic::Ordering::SeqCst);
-        let stdin = io::stdin();
-        return Ok(stdin.lock().lines());
+    /// Read multi-line input from stdin, suited for pasting documents at the terminal
+    ///
+    /// When stdin is an interactive terminal, a short hint is shown on stderr and input ends at a line
+    /// consisting only of `terminator` (E.g. `"."`), which is not included. When stdin is piped, the
+    /// entire input is read.
+    /// ```rust,no_run
+    /// use clap_stdin::Stdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let document = Stdin.read_multiline(".")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_multiline(&self, terminator: &str) -> Result<String, StdinError> {
+        claim_stdin(std::any::type_name::<Self>())?;
+        Ok(terminal::read_stdin_multiline(terminator)?)
+    }
+
+    /// Iterate over the lines of stdin (without line endings), decoded according to the crate
+    /// default [`Utf8Policy`]
+    pub fn lines(&self) -> Result<impl Iterator<Item = io::Result<String>>, StdinError> {
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        Ok(decode_lines(reader, Utf8Policy::current_default()))
     }
+
+    /// Like [`Stdin::lines`], reading `stdin` as configured by `opts`, E.g. skipping the lines
+    /// filtered by [`StdinOpts::skip_blank`] and [`StdinOpts::comment_prefix`]
+    /// ```rust
+    /// use clap_stdin::{Stdin, StdinOpts, StdinSession};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let _session = StdinSession::with_input("# hosts\nexample.com\n\nexample.org\n");
+    /// let opts = StdinOpts::new().skip_blank().comment_prefix("#");
+    /// let hosts = Stdin.lines_with(&opts)?.collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(hosts, ["example.com", "example.org"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lines_with(
+        &self,
+        opts: &StdinOpts,
+    ) -> Result<impl Iterator<Item = io::Result<String>>, StdinError> {
+        let reader = opts.text_reader(self.reader(std::any::type_name::<Self>(), Some(opts))?);
+        let skipped = opts.line_filter();
+        Ok(decode_lines(
+            SkipBom::unless_kept(reader, opts.keep_bom),
+            opts.utf8_policy(),
+        )
+        .filter(move |line| !matches!(line, Ok(line) if skipped(line))))
+    }
+
+    /// Iterate over the records of stdin, split on `separator` (E.g. `"\x1e"` for JSON text
+    /// sequences, or `"\n---\n"` for YAML multi-document streams)
+    /// ```rust,no_run
+    /// use clap_stdin::Stdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// for document in Stdin.records("\n---\n")? {
+    ///     println!("{}", document?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn records(&self, separator: &str) -> Result<Records<BoxedReader>, StdinError> {
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        Ok(Records::new(reader, separator))
+    }
+
+    /// Like [`Stdin::lines`], yielding each line with its 1-based line number
+    /// ```rust,no_run
+    /// use clap_stdin::Stdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// for line in Stdin.lines_numbered()? {
+    ///     let (number, line) = line?;
+    ///     println!("<stdin>:{number}: {line}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lines_numbered(
+        &self,
+    ) -> Result<impl Iterator<Item = io::Result<(usize, String)>> + '_, StdinError> {
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        Ok(number_lines(decode_lines(
+            reader,
+            Utf8Policy::current_default(),
+        )))
+    }
+
+    /// Iterate over the bytes of stdin
+    pub fn bytes(&self) -> Result<io::Bytes<BoxedReader>, StdinError> {
+        Ok(self.reader(std::any::type_name::<Self>(), None)?.bytes())
+    }
+
+    /// Read exactly `n` bytes from stdin
+    ///
+    /// This does not mark stdin as read, so the rest of the input can still be consumed
+    /// afterwards (E.g. a fixed-length header followed by the payload).
+    /// ```rust,no_run
+    /// use clap_stdin::Stdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let header = Stdin.read_exact_bytes(4)?;
+    /// let len = u32::from_be_bytes(header.try_into().unwrap());
+    /// let payload = Stdin.read_exact_bytes(len as usize)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_exact_bytes(&self, n: usize) -> Result<Vec<u8>, StdinError> {
+        ensure_stdin_unclaimed()?;
+        let mut buf = vec![0; n];
+        with_stdin(|stdin| stdin.read_exact(&mut buf))?;
+        Ok(buf)
+    }
+
+    /// Read a single line from stdin, without the trailing newline
+    ///
+    /// Returns `None` if stdin is already at EOF. Like [`Stdin::read_exact_bytes`], this does not mark
+    /// stdin as read, so the rest of the input can be consumed afterwards.
+    /// ```rust,no_run
+    /// use clap_stdin::Stdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let token = Stdin.read_line_once()?.unwrap_or_default();
+    /// let payload = Stdin.read_string()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_line_once(&self) -> Result<Option<String>, StdinError> {
+        ensure_stdin_unclaimed()?;
+        let mut line = String::new();
+        if with_stdin(|stdin| stdin.read_line(&mut line))? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+
+    /// Stream the contents of stdin into `writer`, returning the number of bytes copied
+    ///
+    /// Useful for filter-style tools that pass the input through without buffering it all in memory.
+    /// ```rust,no_run
+    /// use clap_stdin::Stdin;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let copied = Stdin.copy_to(&mut std::io::stdout())?;
+    /// eprintln!("copied {copied} bytes");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_to<W>(&self, writer: &mut W) -> Result<u64, StdinError>
+    where
+        W: io::Write + ?Sized,
+    {
+        self.copy_to_with_buffer(writer, DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Like [`Stdin::copy_to`], reading stdin in chunks of `buffer_size` bytes
+    pub fn copy_to_with_buffer<W>(
+        &self,
+        writer: &mut W,
+        buffer_size: usize,
+    ) -> Result<u64, StdinError>
+    where
+        W: io::Write + ?Sized,
+    {
+        let mut stdin = self.reader(std::any::type_name::<Self>(), None)?;
+        let mut buf = vec![0; buffer_size.max(1)];
+        let mut copied = 0;
+        loop {
+            let n = match stdin.read(&mut buf)? {
+                0 => break,
+                n => n,
+            };
+            writer.write_all(&buf[..n])?;
+            copied += n as u64;
+        }
+        writer.flush()?;
+        Ok(copied)
+    }
+}
+
+#[test]
+fn test_source_reading() {
+    let source: Source = "a\r\nb\n".parse().unwrap();
+    assert_eq!(source.source_kind(), SourceKind::Literal);
+    assert_eq!(source.clone().contents().unwrap(), "a\r\nb\n");
+    let lines = source
+        .lines()
+        .unwrap()
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(lines, ["a", "b"]);
+    assert_eq!(Source::stdin().source_kind(), SourceKind::Stdin);
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_source_kind_name() {
+    let kind = SourceKind::File("/secret/path".into());
+    assert_eq!(kind.name(), "a file");
+    assert_eq!(
+        SourceKind::Env("TOKEN".into()).name(),
+        "an environment variable"
+    );
 }
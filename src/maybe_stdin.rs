@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
-use super::{Source, StdinError};
+use super::guard::ensure_stdin_unclaimed;
+use super::session::stdin_is_terminal;
+use super::{terminal, Source, SourceKind, StdinError, StdinOpts, Trim};
 
 /// Wrapper struct to parse arg values from `stdin`
 ///
@@ -27,13 +29,48 @@ use super::{Source, StdinError};
 #[derive(Clone)]
 pub struct MaybeStdin<T> {
     inner: T,
-    is_stdin: bool,
+    source: SourceKind,
 }
 
 impl<T> MaybeStdin<T> {
-    /// Was this value read from stdin
+    /// Wrap a value that was provided directly (not read from stdin)
+    ///
+    /// ```rust
+    /// use clap_stdin::MaybeStdin;
+    ///
+    /// let value = MaybeStdin::new(42);
+    /// assert_eq!(*value, 42);
+    /// assert!(!value.is_stdin());
+    /// ```
+    pub fn new(value: T) -> Self {
+        Self::from_value(value, SourceKind::Literal)
+    }
+
+    /// Wrap a value, recording where it originated from
+    ///
+    /// Useful for synthesizing a `MaybeStdin` outside of argument parsing
+    /// (E.g. from a config file) while keeping the provenance checked by [`MaybeStdin::is_stdin`]
+    /// ```rust
+    /// use clap_stdin::{MaybeStdin, SourceKind};
+    ///
+    /// let value = MaybeStdin::from_value(42, SourceKind::File("config.toml".into()));
+    /// assert!(!value.is_stdin());
+    /// ```
+    pub fn from_value(value: T, source: SourceKind) -> Self {
+        Self {
+            inner: value,
+            source,
+        }
+    }
+
+    /// Where this value originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was this value read from stdin (shorthand for checking [`MaybeStdin::source_kind`])
     pub fn is_stdin(&self) -> bool {
-        self.is_stdin
+        self.source == SourceKind::Stdin
     }
 }
 
@@ -45,24 +82,320 @@ where
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let source = Source::from_str(s)?;
-        let is_stdin = matches!(source, Source::Stdin(_));
-        T::from_str(source.get_value()?.trim())
-            .map_err(|e| StdinError::FromStr(format!("{e}")))
-            .map(|val| Self {
-                inner: val,
-                is_stdin,
-            })
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// With the `clap` feature, clap parses `MaybeStdin` args with a [`StdinValueParser`](crate::StdinValueParser),
+/// which names the arg in error messages
+#[cfg(feature = "clap")]
+impl<T> clap::builder::ValueParserFactory for MaybeStdin<T>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    type Parser = crate::StdinValueParser<Self>;
+
+    fn value_parser() -> Self::Parser {
+        crate::StdinValueParser::new(<Self as FromStr>::from_str)
     }
 }
 
 impl<T> MaybeStdin<T> {
+    /// Get the raw value of the arg, reading it from stdin if the arg is the sentinel ("-")
+    fn resolve(s: &str, opts: &StdinOpts) -> Result<(String, SourceKind), StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let value = source.get_value_with(std::any::type_name::<Self>(), opts)?;
+        debug_log!(
+            "{}: resolved to {} ({} bytes)",
+            std::any::type_name::<Self>(),
+            kind.name(),
+            value.len()
+        );
+        Ok((value, kind))
+    }
+
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        Self::parse_with_default(s, opts, None)
+    }
+
+    /// Parse like [`MaybeStdin::parse_with_opts`], resolving an empty value to `default` if given
+    fn parse_with_default(
+        s: &str,
+        opts: &StdinOpts,
+        default: Option<fn() -> T>,
+    ) -> Result<Self, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let (value, kind) = match Self::resolve(s, opts) {
+            Err(StdinError::StdinClosed) if default.is_some() => (String::new(), SourceKind::Stdin),
+            resolved => resolved?,
+        };
+        let trimmed = opts.trim_or(Trim::Whitespace).apply(&value);
+        if let Some(default) = default.filter(|_| trimmed.is_empty()) {
+            return Ok(Self::from_value(default(), kind));
+        }
+        T::from_str(trimmed)
+            .map_err(|e| match kind {
+                SourceKind::Literal => StdinError::invalid_arg(trimmed, format!("{e}")),
+                _ => StdinError::FromStr(format!("{e}")),
+            })
+            .map(|val| Self::from_value(val, kind))
+    }
+
+    /// Read and parse the value of `source` through the `futures` I/O traits, without blocking
+    /// the async runtime
+    ///
+    /// Declare the arg as a [`Source`](crate::Source) to read it once the runtime is running:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, Source};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     value: Source,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse_from(["example", "42"]);
+    /// let value = futures_lite::future::block_on(MaybeStdin::<u32>::from_source_futures(args.value))?;
+    /// assert_eq!(*value, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn from_source_futures(source: crate::Source) -> Result<Self, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let kind = source.source_kind();
+        let value = source.contents_futures().await?;
+        T::from_str(Trim::Whitespace.apply(&value))
+            .map_err(|e| StdinError::FromStr(format!("{e}")))
+            .map(|val| Self::from_value(val, kind))
+    }
+
+    /// A `value_parser` that trims the value according to `trim` before parsing it
+    ///
+    /// By default, [`Trim::Whitespace`] is applied. Use [`Trim::None`] when whitespace is significant
+    /// (E.g. comparing tokens), or [`Trim::TrailingNewline`] to only remove the newline added by `echo`:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, Trim};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = MaybeStdin::<String>::with_trim(Trim::None))]
+    ///     token: MaybeStdin<String>,
+    /// }
+    ///
+    /// let args = Args::parse_from(["example", " padded "]);
+    /// assert_eq!(*args.token, " padded ");
+    /// ```
+    pub fn with_trim(
+        trim: Trim,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        Self::with_opts(StdinOpts::new().trim(trim))
+    }
+
+    /// A `value_parser` that reads and parses the value as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// Read the value from `stdin` when an optional arg was omitted, but only if `stdin` is piped
+    ///
+    /// Like many Unix tools, `echo value | ./example` then works the same as `./example value`,
+    /// while running `./example` from a terminal leaves the value `None` instead of waiting for
+    /// input. `stdin` is also skipped if it's empty, or was already read by another arg.
+    ///
+    /// A pipe that stays open without any data (E.g. the `stdin` of some CI runners) is waited on
+    /// like any other read: set [`StdinOpts::timeout`] (or [`TIMEOUT_ENV`](crate::TIMEOUT_ENV))
+    /// to fail with [`StdinError::Timeout`] instead. The other options apply like for
+    /// [`MaybeStdin::with_opts`].
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     name: Option<MaybeStdin<String>>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// match MaybeStdin::or_piped_stdin(args.name, &StdinOpts::default())? {
+    ///     Some(name) => println!("Hello, {name}!"),
+    ///     None => println!("Hello!"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn or_piped_stdin(value: Option<Self>, opts: &StdinOpts) -> Result<Option<Self>, StdinError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        if value.is_some() || stdin_is_terminal() || ensure_stdin_unclaimed().is_err() {
+            return Ok(value);
+        }
+        match Source::stdin().get_value_with(std::any::type_name::<Self>(), opts) {
+            Ok(value) if value.trim().is_empty() => Ok(None),
+            Ok(value) => T::from_str(opts.trim_or(Trim::Whitespace).apply(&value))
+                .map_err(|e| StdinError::FromStr(format!("{e}")))
+                .map(|val| Some(Self::from_value(val, SourceKind::Stdin))),
+            Err(StdinError::StdinClosed) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parse like [`FromStr`], but resolve an empty value (from stdin or the arg) to `T::default()`
+    ///
+    /// Use it as a `value_parser` for args where an empty pipe means "nothing to do":
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::MaybeStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = MaybeStdin::<u32>::parse_or_default)]
+    ///     count: MaybeStdin<u32>,
+    /// }
+    ///
+    /// let args = Args::parse_from(["example", ""]);
+    /// assert_eq!(*args.count, 0);
+    /// ```
+    ///
+    /// Use [`MaybeStdin::with_opts_or_default`] to configure how the value is read.
+    pub fn parse_or_default(s: &str) -> Result<Self, StdinError>
+    where
+        T: FromStr + Default,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        Self::parse_with_default(s, &StdinOpts::default(), Some(T::default))
+    }
+
+    /// A `value_parser` like [`MaybeStdin::with_opts`], but resolving an empty value to `T::default()`
+    ///
+    /// The value is empty if it is after applying the [`Trim`] of `opts`:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, StdinOpts, Trim};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = MaybeStdin::<String>::with_opts_or_default(StdinOpts::new().trim(Trim::TrailingNewline)))]
+    ///     prefix: MaybeStdin<String>,
+    /// }
+    ///
+    /// let args = Args::parse_from(["example", " "]);
+    /// assert_eq!(*args.prefix, " ");
+    /// ```
+    pub fn with_opts_or_default(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr + Default,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        move |s| Self::parse_with_default(s, &opts, Some(T::default))
+    }
+
+    /// Require interactive confirmation if the value matches `predicate`
+    ///
+    /// The user is asked to confirm with `prompt` on the terminal (even if the value itself was piped
+    /// through `stdin`), unless `assume_yes` is set (E.g. from a `--yes` flag). Fails with
+    /// [`StdinError::NotConfirmed`] if the user declines, or [`StdinError::NoTerminal`] if there is
+    /// no terminal to ask on.
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::MaybeStdin;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     target: MaybeStdin<String>,
+    ///     #[arg(long)]
+    ///     yes: bool,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse();
+    /// let target = args.target.confirm_if(
+    ///     |target| target == "prod",
+    ///     "Really deploy to prod?",
+    ///     args.yes,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn confirm_if(
+        self,
+        predicate: impl FnOnce(&T) -> bool,
+        prompt: &str,
+        assume_yes: bool,
+    ) -> Result<Self, StdinError> {
+        if !assume_yes && predicate(&self.inner) {
+            terminal::confirm(prompt)?;
+        }
+        Ok(self)
+    }
+
     /// Extract the inner value from the wrapper
     pub fn into_inner(self) -> T {
         self.inner
     }
 }
 
+/// The default value of `T`, with [`SourceKind::Default`]
+///
+/// With `Display`, this lets clap's `default_value_t` provide the default of an arg. clap parses
+/// the rendered default like any arg value, so its source is [`SourceKind::Literal`]:
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{MaybeStdin, SourceKind};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long, default_value_t)]
+///     retries: MaybeStdin<u32>,
+/// }
+///
+/// let args = Args::parse_from(["example"]);
+/// assert_eq!(*args.retries, 0);
+/// assert_eq!(args.retries.source_kind(), SourceKind::Literal);
+/// ```
+impl<T: Default> Default for MaybeStdin<T> {
+    fn default() -> Self {
+        Self::from_value(T::default(), SourceKind::Default)
+    }
+}
+
+/// Wrap a value provided directly, with [`SourceKind::Literal`] (like [`MaybeStdin::new`])
+impl<T> From<T> for MaybeStdin<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
 impl<T> std::fmt::Display for MaybeStdin<T>
 where
     T: std::fmt::Display,
@@ -77,7 +410,10 @@ where
     T: std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+        f.debug_struct("MaybeStdin")
+            .field("source", &self.source)
+            .field("value", &self.inner)
+            .finish()
     }
 }
 
@@ -94,3 +430,84 @@ impl<T> std::ops::DerefMut for MaybeStdin<T> {
         &mut self.inner
     }
 }
+
+#[test]
+fn test_debug_output() {
+    let val = MaybeStdin::new(String::from("value"));
+    assert_eq!(
+        format!("{val:?}"),
+        r#"MaybeStdin { source: Literal, value: "value" }"#
+    );
+}
+
+#[test]
+fn test_parse_or_default() {
+    let val = MaybeStdin::<u32>::parse_or_default("  ").unwrap();
+    assert_eq!(*val, 0);
+    assert!(!val.is_stdin());
+    assert_eq!(*MaybeStdin::<u32>::parse_or_default("7").unwrap(), 7);
+    assert!(MaybeStdin::<u32>::parse_or_default("seven").is_err());
+    assert!(MaybeStdin::<u32>::from_str("").is_err());
+
+    let parse = MaybeStdin::<u32>::with_opts_or_default(StdinOpts::new().trim(Trim::None));
+    assert_eq!(*parse("").unwrap(), 0);
+    assert!(parse(" ").is_err());
+    let parse = MaybeStdin::<u32>::with_opts_or_default(StdinOpts::new().sentinel("@"));
+    let _session = crate::StdinSession::with_input("\n");
+    let val = parse("@").unwrap();
+    assert!(val.is_stdin());
+    assert_eq!(*val, 0);
+}
+
+#[test]
+fn test_confirm_if_skipped() {
+    let val = MaybeStdin::new(String::from("dev"));
+    let val = val.confirm_if(|v| v == "prod", "Deploy?", false).unwrap();
+    assert_eq!(*val, "dev");
+    let val = MaybeStdin::new(String::from("prod"));
+    assert!(val.confirm_if(|v| v == "prod", "Deploy?", true).is_ok());
+}
+
+#[test]
+fn test_or_piped_stdin() {
+    let opts = StdinOpts::default();
+    let value = MaybeStdin::or_piped_stdin(Some(MaybeStdin::new(1)), &opts).unwrap();
+    assert_eq!(value.map(|v| *v), Some(1));
+
+    let _session = crate::StdinSession::with_input("2\n");
+    let value = MaybeStdin::<u32>::or_piped_stdin(None, &opts)
+        .unwrap()
+        .unwrap();
+    assert!(value.is_stdin());
+    assert_eq!(*value, 2);
+    // stdin was already read
+    assert!(MaybeStdin::<u32>::or_piped_stdin(None, &opts)
+        .unwrap()
+        .is_none());
+
+    let _session = crate::StdinSession::with_input(" \n");
+    assert!(MaybeStdin::<u32>::or_piped_stdin(None, &opts)
+        .unwrap()
+        .is_none());
+
+    // Closed without any input
+    let _session = crate::StdinSession::with_input("");
+    assert!(MaybeStdin::<u32>::or_piped_stdin(None, &opts)
+        .unwrap()
+        .is_none());
+
+    let _session = crate::StdinSession::with_input(" 3 ");
+    let opts = StdinOpts::new().trim(Trim::None);
+    assert!(matches!(
+        MaybeStdin::<u32>::or_piped_stdin(None, &opts),
+        Err(StdinError::FromStr(_))
+    ));
+}
+
+#[test]
+fn test_with_trim() {
+    let parse = MaybeStdin::<String>::with_trim(Trim::TrailingNewline);
+    assert_eq!(*parse(" value \n").unwrap(), " value ");
+    let parse = MaybeStdin::<String>::with_trim(Trim::Whitespace);
+    assert_eq!(*parse(" value \n").unwrap(), "value");
+}
@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use super::{Source, SourceKind, StdinError};
+
+/// Wrapper struct for list values given either as repeated args, or as a JSON array via `stdin`
+///
+/// Used as the item type of a `Vec` field, each arg value parses into one item with `FromStr`,
+/// while the `-` value reads a JSON array of items from `stdin` (a single JSON value is one item).
+/// With the `yaml` feature, `stdin` that isn't valid JSON is read as a YAML sequence instead.
+/// Iterating a `MaybeStdinArray` yields its items, so the field flattens into one list:
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinArray;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long = "id")]
+///     ids: Vec<MaybeStdinArray<u32>>,
+/// }
+///
+/// let args = Args::parse_from(["example", "--id", "1", "--id", "2"]);
+/// let ids: Vec<u32> = args.ids.into_iter().flatten().collect();
+/// assert_eq!(ids, [1, 2]);
+/// ```
+///
+/// ```sh
+/// $ ./example --id 1 --id 2
+/// $ echo '[1, 2]' | ./example --id -
+/// $ printf -- '- 1\n- 2\n' | ./example --id -  # with the `yaml` feature
+/// ```
+#[derive(Clone)]
+pub struct MaybeStdinArray<T> {
+    inner: Vec<T>,
+    source: SourceKind,
+}
+
+impl<T> MaybeStdinArray<T> {
+    /// Parse the items of a JSON array (or a single JSON value), falling back to a YAML sequence
+    /// with the `yaml` feature
+    fn from_document(contents: &str) -> Result<Vec<T>, StdinError>
+    where
+        T: DeserializeOwned,
+    {
+        let value: serde_json::Value = match serde_json::from_str(contents) {
+            Ok(value) => value,
+            #[cfg(feature = "yaml")]
+            Err(_) => return Self::from_yaml(contents),
+            #[cfg(not(feature = "yaml"))]
+            Err(e) => return Err(StdinError::Deserialize(format!("{e}"))),
+        };
+        let items = match value {
+            serde_json::Value::Array(items) => items,
+            value => vec![value],
+        };
+        items
+            .into_iter()
+            .map(|item| {
+                serde_json::from_value(item).map_err(|e| StdinError::Deserialize(format!("{e}")))
+            })
+            .collect()
+    }
+
+    /// Parse the items of a YAML sequence (or a single YAML value)
+    #[cfg(feature = "yaml")]
+    fn from_yaml(yaml: &str) -> Result<Vec<T>, StdinError>
+    where
+        T: DeserializeOwned,
+    {
+        let value: serde_yaml::Value =
+            serde_yaml::from_str(yaml).map_err(|e| StdinError::Deserialize(format!("{e}")))?;
+        let items = match value {
+            serde_yaml::Value::Sequence(items) => items,
+            value => vec![value],
+        };
+        items
+            .into_iter()
+            .map(|item| {
+                serde_yaml::from_value(item).map_err(|e| StdinError::Deserialize(format!("{e}")))
+            })
+            .collect()
+    }
+
+    /// Where these items originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these items read from stdin (shorthand for checking the `source_kind`)
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the items from the wrapper
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T> FromStr for MaybeStdinArray<T>
+where
+    T: FromStr + DeserializeOwned,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = Source::from_str(s)?;
+        let source_kind = source.source_kind();
+        let inner = match source {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => {
+                Self::from_document(&source.get_value(std::any::type_name::<Self>())?)?
+            }
+            Source::Arg(value) => {
+                vec![T::from_str(&value).map_err(|e| StdinError::FromStr(format!("{e}")))?]
+            }
+            Source::Path(_) => {
+                let value = source.get_value(std::any::type_name::<Self>())?;
+                vec![T::from_str(&value).map_err(|e| StdinError::FromStr(format!("{e}")))?]
+            }
+        };
+        Ok(Self {
+            inner,
+            source: source_kind,
+        })
+    }
+}
+
+impl<T> IntoIterator for MaybeStdinArray<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<T> std::fmt::Debug for MaybeStdinArray<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinArray")
+            .field("source", &self.source)
+            .field("value", &self.inner)
+            .finish()
+    }
+}
+
+impl<T> std::ops::Deref for MaybeStdinArray<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+#[test]
+fn test_from_json() {
+    assert_eq!(
+        MaybeStdinArray::<u32>::from_document("[1, 2, 3]").unwrap(),
+        [1, 2, 3]
+    );
+    assert_eq!(
+        MaybeStdinArray::<String>::from_document(r#""a""#).unwrap(),
+        ["a"]
+    );
+    assert!(MaybeStdinArray::<u32>::from_document(r#"["a"]"#).is_err());
+
+    let items: MaybeStdinArray<String> = "value".parse().unwrap();
+    assert_eq!(items.into_inner(), ["value"]);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_from_yaml() {
+    assert_eq!(
+        MaybeStdinArray::<u32>::from_document("- 1\n- 2\n").unwrap(),
+        [1, 2]
+    );
+    assert!(MaybeStdinArray::<u32>::from_document("- a\n").is_err());
+
+    let _session = crate::StdinSession::with_input("- 3\n- 4\n");
+    let items: MaybeStdinArray<u32> = "-".parse().unwrap();
+    assert!(items.is_stdin());
+    assert_eq!(items.into_inner(), [3, 4]);
+}
@@ -0,0 +1,151 @@
+use std::str::FromStr;
+
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+
+use super::{SourceKind, StdinError, StdinOpts};
+
+/// Standard alphabet, with or without padding
+const ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Wrapper struct for base64 encoded arg values, or base64 encoded input from `stdin`
+///
+/// The value is decoded (with the standard alphabet, padding optional) before the bytes are
+/// converted into `T` with `TryFrom<Vec<u8>>`, so binary payloads can be passed through channels
+/// that only carry text, without piping them through `base64 -d`. Whitespace is ignored, so
+/// wrapped output of `base64` can be piped in as is.
+///
+/// `T` defaults to the raw bytes, and can be any type converted from them, like `String`:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinBase64;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     key: MaybeStdinBase64,
+///     #[arg(long)]
+///     note: Option<MaybeStdinBase64<String>>,
+/// }
+///
+/// let args = Args::parse();
+/// println!("{} byte key", args.key.len());
+/// ```
+///
+/// ```sh
+/// $ ./example c2VjcmV0IGtleQ==
+/// $ base64 key.bin | ./example -
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct MaybeStdinBase64<T = Vec<u8>> {
+    inner: T,
+    source: SourceKind,
+}
+
+impl<T> MaybeStdinBase64<T>
+where
+    T: TryFrom<Vec<u8>>,
+    T::Error: std::fmt::Display,
+{
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let mut encoded = source.get_bytes_with(std::any::type_name::<Self>(), opts)?;
+        encoded.retain(|b| !b.is_ascii_whitespace());
+        let bytes = ENGINE
+            .decode(&encoded)
+            .map_err(|e| StdinError::Base64(format!("{e}")))?;
+        let inner = T::try_from(bytes).map_err(|e| StdinError::FromStr(format!("{e}")))?;
+        Ok(Self {
+            inner,
+            source: kind,
+        })
+    }
+
+    /// A `value_parser` that reads the encoded value as configured by `opts`
+    ///
+    /// The trim options don't apply, since whitespace is always ignored.
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+}
+
+impl<T> MaybeStdinBase64<T> {
+    /// Where the encoded value originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was the encoded value read from stdin (shorthand for checking the `source_kind`)
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the decoded value from the wrapper
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> FromStr for MaybeStdinBase64<T>
+where
+    T: TryFrom<Vec<u8>>,
+    T::Error: std::fmt::Display,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// The decoded value isn't printed, since it's commonly a key or other secret
+impl<T> std::fmt::Debug for MaybeStdinBase64<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinBase64")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> std::ops::Deref for MaybeStdinBase64<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for MaybeStdinBase64<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[test]
+fn test_maybe_stdin_base64() {
+    let bytes: MaybeStdinBase64 = "/wCJ".parse().unwrap();
+    assert_eq!(bytes.source_kind(), SourceKind::Literal);
+    assert_eq!(*bytes, [0xff, 0x00, 0x89]);
+
+    let _session = crate::StdinSession::with_input("aGVsbG8g\nd29ybGQ=\n");
+    let text: MaybeStdinBase64<String> = "-".parse().unwrap();
+    assert!(text.is_stdin());
+    assert_eq!(text.into_inner(), "hello world");
+
+    let text: MaybeStdinBase64<String> = "aGk".parse().unwrap();
+    assert_eq!(*text, "hi");
+
+    assert!(matches!(
+        "not base64!".parse::<MaybeStdinBase64>(),
+        Err(StdinError::Base64(_))
+    ));
+    assert!(matches!(
+        "/wCJ".parse::<MaybeStdinBase64<String>>(),
+        Err(StdinError::FromStr(_))
+    ));
+}
@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use super::{SourceKind, StdinError, StdinOpts};
+
+/// Wrapper struct for raw binary arg values, or the raw contents of `stdin`
+///
+/// Unlike [`MaybeStdin<String>`](crate::MaybeStdin), the contents of `stdin` are kept as bytes and
+/// never go through UTF-8 validation, so binary payloads (E.g. images or protobufs) can be piped in.
+/// Use [`FileOrStdin::contents_bytes`](crate::FileOrStdin::contents_bytes) to read a binary file
+/// from a path instead.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinBytes;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     payload: MaybeStdinBytes,
+/// }
+///
+/// let args = Args::parse();
+/// println!("{} bytes", args.payload.len());
+/// ```
+///
+/// ```sh
+/// $ cat image.png | ./example -
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct MaybeStdinBytes {
+    inner: Vec<u8>,
+    source: SourceKind,
+}
+
+impl MaybeStdinBytes {
+    /// Wrap bytes, recording where they originated from
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>, source: SourceKind) -> Self {
+        Self {
+            inner: bytes.into(),
+            source,
+        }
+    }
+
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let bytes = source.get_bytes_with(std::any::type_name::<Self>(), opts)?;
+        Ok(Self::from_bytes(bytes, kind))
+    }
+
+    /// A `value_parser` that reads the value as configured by `opts`
+    ///
+    /// Only the sentinel and size limit options apply: the bytes are never trimmed.
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// Where these bytes originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these bytes read from stdin (shorthand for checking [`MaybeStdinBytes::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the bytes from the wrapper
+    pub fn into_inner(self) -> Vec<u8> {
+        self.inner
+    }
+}
+
+impl FromStr for MaybeStdinBytes {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// With the `clap` feature, clap parses `MaybeStdinBytes` args with a
+/// [`StdinValueParser`](crate::StdinValueParser), which names the arg in error messages
+#[cfg(feature = "clap")]
+impl clap::builder::ValueParserFactory for MaybeStdinBytes {
+    type Parser = crate::StdinValueParser<Self>;
+
+    fn value_parser() -> Self::Parser {
+        crate::StdinValueParser::new(<Self as FromStr>::from_str)
+    }
+}
+
+impl std::fmt::Debug for MaybeStdinBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinBytes")
+            .field("source", &self.source)
+            .field("len", &self.inner.len())
+            .finish()
+    }
+}
+
+impl std::ops::Deref for MaybeStdinBytes {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl std::ops::DerefMut for MaybeStdinBytes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl AsRef<[u8]> for MaybeStdinBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+#[test]
+fn test_maybe_stdin_bytes() {
+    let _session = crate::StdinSession::with_input([0xff, 0x00, 0x89]);
+    let bytes: MaybeStdinBytes = "-".parse().unwrap();
+    assert!(bytes.is_stdin());
+    assert_eq!(*bytes, [0xff, 0x00, 0x89]);
+
+    let bytes: MaybeStdinBytes = "text".parse().unwrap();
+    assert_eq!(bytes.source_kind(), SourceKind::Literal);
+    assert_eq!(bytes.into_inner(), b"text");
+
+    let _session = crate::StdinSession::with_input("too large");
+    let parse = MaybeStdinBytes::with_opts(StdinOpts::new().max_bytes(4));
+    assert!(matches!(parse("-"), Err(StdinError::InputTooLarge(4))));
+}
@@ -1,7 +1,6 @@
-use core::panic;
 use std::str::FromStr;
 
-use super::{Source, StdinError};
+use super::{Source, SourceKind, StdinError, StdinOpts};
 
 /// Wrapper struct to parse arg values from `stdin`
 ///
@@ -34,10 +33,12 @@ use super::{Source, StdinError};
 ///                     .collect::<Result<Vec<String>, _>>()?;
 ///                 Ok(Fruits(fruits))
 ///             }
+///             Source::SharedStdin(stdin) => Ok(Fruits(stdin.lines()?.collect::<Result<_, _>>()?)),
 ///             Source::Arg(arg) => {
 ///                 let fruits = arg.split(",").map(String::from).collect::<Vec<_>>();
 ///                 Ok(Fruits(fruits))
 ///             }
+///             source @ (Source::Path(_) | Source::Fd(_)) => Ok(Fruits(vec![source.try_into()?])),
 ///         }
 ///     }
 /// }
@@ -55,12 +56,26 @@ use super::{Source, StdinError};
 #[derive(Clone)]
 pub struct MaybeStdinFromSource<T> {
     inner: T,
-    is_stdin: bool,
+    source: SourceKind,
 }
 
 impl<T> MaybeStdinFromSource<T> {
+    /// Wrap a value, recording where it originated from
+    pub fn from_value(value: T, source: SourceKind) -> Self {
+        Self {
+            inner: value,
+            source,
+        }
+    }
+
+    /// Where this value originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was this value read from stdin (shorthand for checking the `source_kind`)
     pub fn is_stdin(&self) -> bool {
-        self.is_stdin
+        self.source == SourceKind::Stdin
     }
 }
 
@@ -80,138 +95,104 @@ where
     type Err = StdinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let source = Source::from_str(s)?;
-        let is_stdin = matches!(source, Source::Stdin(_));
-        T::from_source(source)
-            .map_err(|e| StdinError::FromStr(format!("{e}")))
-            .map(|val| Self {
-                inner: val,
-                is_stdin,
-            })
-    }
-}
-
-impl<T> MaybeStdinFromSource<T> {
-    pub fn into_inner(self) -> T {
-        self.inner
+        Self::parse_with_opts(s, &StdinOpts::default())
     }
 }
 
-impl<T> std::fmt::Display for MaybeStdinFromSource<T>
+/// With the `clap` feature, clap parses `MaybeStdinFromSource` args with a [`StdinValueParser`](crate::StdinValueParser),
+/// which names the arg in error messages
+#[cfg(feature = "clap")]
+impl<T> clap::builder::ValueParserFactory for MaybeStdinFromSource<T>
 where
-    T: std::fmt::Display,
+    T: FromSource + Clone + Send + Sync + 'static,
+    T::Err: std::fmt::Display,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+    type Parser = crate::StdinValueParser<Self>;
+
+    fn value_parser() -> Self::Parser {
+        crate::StdinValueParser::new(<Self as FromStr>::from_str)
     }
 }
 
-impl<T> std::fmt::Debug for MaybeStdinFromSource<T>
+impl<T> MaybeStdinFromSource<T>
 where
-    T: std::fmt::Debug,
+    T: FromSource,
+    T::Err: std::fmt::Display,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+    /// A `value_parser` that passes a [`Source::Stdin`] to `T` for the sentinel configured in
+    /// `opts` (E.g. `@-`), instead of `-`
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
     }
-}
-
-impl<T> std::ops::Deref for MaybeStdinFromSource<T> {
-    type Target = T;
 
-    fn deref(&self) -> &Self::Target {
-        &self.inner
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        debug_log!(
+            "{}: resolved to {}",
+            std::any::type_name::<Self>(),
+            kind.name()
+        );
+        T::from_source(source)
+            .map_err(|e| StdinError::FromStr(format!("{e}")))
+            .map(|val| Self::from_value(val, kind))
     }
 }
 
-impl<T> std::ops::DerefMut for MaybeStdinFromSource<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+impl<T> MaybeStdinFromSource<T> {
+    pub fn into_inner(self) -> T {
+        self.inner
     }
 }
 
-#[derive(Clone)]
-pub struct MaybeStdinVec<T, const D: char = ','> {
-    inner: Vec<T>,
-    is_stdin: bool,
-}
-
-impl<T, const D: char> MaybeStdinVec<T, D> {
-    pub fn is_stdin(&self) -> bool {
-        self.is_stdin
+/// The default value of `T`, with [`SourceKind::Default`]
+///
+/// A `default_value_t` arg is parsed from the `Display` of this value instead, with
+/// [`SourceKind::Literal`].
+impl<T: Default> Default for MaybeStdinFromSource<T> {
+    fn default() -> Self {
+        Self::from_value(T::default(), SourceKind::Default)
     }
 }
 
-impl<T, const D: char> FromStr for MaybeStdinVec<T, D>
-where
-    T: FromStr,
-    T::Err: std::fmt::Display,
-{
-    type Err = StdinError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let source = Source::from_str(s)?;
-        let is_stdin = matches!(source, Source::Stdin(_));
-        if is_stdin {
-            source
-                .get_value()?
-                .trim()
-                .lines()
-                .map(|s| T::from_str(s).map_err(|e| StdinError::FromStr(format!("{e}"))))
-                .collect::<Result<Vec<T>, _>>()
-                .map(|inner| Self { inner, is_stdin })
-        } else {
-            source
-                .get_value()?
-                .trim()
-                .split(D)
-                .map(|s| T::from_str(s).map_err(|e| StdinError::FromStr(format!("{e}"))))
-                .collect::<Result<Vec<T>, _>>()
-                .map(|inner| Self { inner, is_stdin })
-        }
+/// Wrap a value provided directly, with [`SourceKind::Literal`]
+impl<T> From<T> for MaybeStdinFromSource<T> {
+    fn from(value: T) -> Self {
+        Self::from_value(value, SourceKind::Literal)
     }
 }
 
-impl<T, const D: char> FromIterator<String> for MaybeStdinVec<T, D>
+impl<T> std::fmt::Display for MaybeStdinFromSource<T>
 where
-    T: FromStr,
-    <T as FromStr>::Err: std::fmt::Display,
+    T: std::fmt::Display,
 {
-    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
-        iter.into_iter()
-            .map(|s| T::from_str(&s))
-            .collect::<Result<Vec<T>, _>>()
-            .map(|inner| Self {
-                inner,
-                is_stdin: false,
-            })
-            .unwrap_or_else(|error| panic!("Failed to parse input: {error}"))
-    }
-}
-
-impl<T, const D: char> MaybeStdinVec<T, D> {
-    pub fn into_inner(self) -> Vec<T> {
-        self.inner
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
     }
 }
 
-impl<T, const D: char> std::fmt::Debug for MaybeStdinVec<T, D>
+impl<T> std::fmt::Debug for MaybeStdinFromSource<T>
 where
     T: std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+        f.debug_struct("MaybeStdinFromSource")
+            .field("source", &self.source)
+            .field("value", &self.inner)
+            .finish()
     }
 }
 
-impl<T, const D: char> std::ops::Deref for MaybeStdinVec<T, D> {
-    type Target = Vec<T>;
+impl<T> std::ops::Deref for MaybeStdinFromSource<T> {
+    type Target = T;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-impl<T, const D: char> std::ops::DerefMut for MaybeStdinVec<T, D> {
+impl<T> std::ops::DerefMut for MaybeStdinFromSource<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.inner
     }
@@ -0,0 +1,170 @@
+use std::str::FromStr;
+
+use super::{SourceKind, StdinError, StdinOpts};
+
+/// Wrapper struct for hex encoded arg values, or hex encoded input from `stdin`
+///
+/// The value is decoded before the bytes are converted into `T` with `TryFrom<&[u8]>`, which
+/// checks the length of fixed-size keys and hashes like `[u8; 32]`. An optional `0x` prefix and
+/// whitespace are accepted, so the output of `xxd -p` can be piped in as is. Only hex digits are
+/// accepted otherwise: the file name printed by `sha256sum` needs to be cut first (E.g. with
+/// `cut -d' ' -f1`).
+///
+/// `T` defaults to the raw bytes:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinHex;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     key: MaybeStdinHex<[u8; 4]>,
+///     #[arg(long)]
+///     fixture: Option<MaybeStdinHex>,
+/// }
+///
+/// let args = Args::parse();
+/// println!("key={:?}", *args.key);
+/// ```
+///
+/// ```sh
+/// $ ./example 0xdeadbeef
+/// $ xxd -p key.bin | ./example -
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct MaybeStdinHex<T = Vec<u8>> {
+    inner: T,
+    source: SourceKind,
+}
+
+impl<T> MaybeStdinHex<T>
+where
+    T: for<'a> TryFrom<&'a [u8]>,
+    for<'a> <T as TryFrom<&'a [u8]>>::Error: std::fmt::Display,
+{
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let encoded = source.get_bytes_with(std::any::type_name::<Self>(), opts)?;
+        let bytes = decode(&encoded)?;
+        let inner =
+            T::try_from(bytes.as_slice()).map_err(|e| StdinError::FromStr(format!("{e}")))?;
+        Ok(Self {
+            inner,
+            source: kind,
+        })
+    }
+
+    /// A `value_parser` that reads the encoded value as configured by `opts`
+    ///
+    /// The trim options don't apply, since whitespace is always ignored.
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+}
+
+impl<T> MaybeStdinHex<T> {
+    /// Where the encoded value originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was the encoded value read from stdin (shorthand for checking the `source_kind`)
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the decoded value from the wrapper
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// Decode hex digits (either case), ignoring whitespace and an optional leading `0x`
+fn decode(encoded: &[u8]) -> Result<Vec<u8>, StdinError> {
+    let digits: Vec<u8> = encoded
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let digits = digits
+        .strip_prefix(b"0x")
+        .or_else(|| digits.strip_prefix(b"0X"))
+        .unwrap_or(&digits);
+    if !digits.len().is_multiple_of(2) {
+        return Err(StdinError::Hex(format!(
+            "odd number of digits ({})",
+            digits.len()
+        )));
+    }
+    let nibble = |b: u8| {
+        (b as char)
+            .to_digit(16)
+            .map(|d| d as u8)
+            .ok_or_else(|| StdinError::Hex(format!("invalid digit {:?}", b as char)))
+    };
+    digits
+        .chunks_exact(2)
+        .map(|pair| Ok(nibble(pair[0])? << 4 | nibble(pair[1])?))
+        .collect()
+}
+
+impl<T> FromStr for MaybeStdinHex<T>
+where
+    T: for<'a> TryFrom<&'a [u8]>,
+    for<'a> <T as TryFrom<&'a [u8]>>::Error: std::fmt::Display,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// The decoded value isn't printed, since it's commonly a key or other secret
+impl<T> std::fmt::Debug for MaybeStdinHex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinHex")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> std::ops::Deref for MaybeStdinHex<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for MaybeStdinHex<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[test]
+fn test_maybe_stdin_hex() {
+    let bytes: MaybeStdinHex = "0xFF0089".parse().unwrap();
+    assert_eq!(bytes.source_kind(), SourceKind::Literal);
+    assert_eq!(*bytes, [0xff, 0x00, 0x89]);
+
+    let _session = crate::StdinSession::with_input(" dead\nbeef \n");
+    let key: MaybeStdinHex<[u8; 4]> = "-".parse().unwrap();
+    assert!(key.is_stdin());
+    assert_eq!(key.into_inner(), [0xde, 0xad, 0xbe, 0xef]);
+
+    assert!(matches!(
+        "abc".parse::<MaybeStdinHex>(),
+        Err(StdinError::Hex(e)) if e.contains("odd")
+    ));
+    assert!(matches!(
+        "0xzz".parse::<MaybeStdinHex>(),
+        Err(StdinError::Hex(e)) if e.contains("'z'")
+    ));
+    assert!(matches!(
+        "dead".parse::<MaybeStdinHex<[u8; 4]>>(),
+        Err(StdinError::FromStr(_))
+    ));
+}
@@ -0,0 +1,196 @@
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use super::{decode_lines, number_lines, Source, SourceKind, StdinError, StdinOpts, Trim};
+
+/// Wrapper struct to lazily parse a list of arg values, or lines from `stdin`
+///
+/// Like [`MaybeStdinVec`](crate::MaybeStdinVec), but only the source is stored at parse time: the
+/// items are read and parsed one at a time while iterating, so huge inputs are never held in memory.
+/// Arg values are split on the delimiter `D`, and `stdin` is split on lines (skipping blank lines).
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinIter;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     ids: MaybeStdinIter<u64>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let mut total = 0;
+/// for id in args.ids {
+///     total += id?;
+/// }
+/// println!("total={total}");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ seq 1 1000000 | ./example -
+/// total=500000500000
+/// $ ./example 1,2,3
+/// total=6
+/// ```
+pub struct MaybeStdinIter<T, const D: char = ','> {
+    source: Source,
+    opts: StdinOpts,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T, const D: char> Clone for MaybeStdinIter<T, D> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            opts: self.opts.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, const D: char> MaybeStdinIter<T, D> {
+    /// A `value_parser` that splits the value as configured by `opts` when iterated
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| {
+            Ok(Self {
+                source: opts.source(s),
+                opts: opts.clone(),
+                _type: PhantomData,
+            })
+        }
+    }
+
+    /// Where the items originate from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.source_kind()
+    }
+
+    /// Are the items read from stdin (shorthand for checking the `source_kind`)
+    pub fn is_stdin(&self) -> bool {
+        self.source.is_stdin()
+    }
+}
+
+impl<T, const D: char> FromStr for MaybeStdinIter<T, D> {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::with_opts(StdinOpts::default())(s)
+    }
+}
+
+impl<T, const D: char> std::fmt::Debug for MaybeStdinIter<T, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinIter")
+            .field("source", &self.source)
+            .field("delimiter", &self.opts.display_delimiter(D))
+            .finish()
+    }
+}
+
+impl<T, const D: char> IntoIterator for MaybeStdinIter<T, D>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    type Item = Result<T, StdinError>;
+    type IntoIter = MaybeStdinItems<T>;
+
+    /// Start reading the items, claiming `stdin` if it is the source
+    fn into_iter(self) -> Self::IntoIter {
+        let trim = self.opts.trim_or(Trim::Whitespace);
+        let utf8 = self.opts.utf8_policy();
+        let skipped = self.opts.line_filter();
+        debug_log!(
+            "{}: reading items from {}",
+            std::any::type_name::<Self>(),
+            self.source.source_kind().name()
+        );
+        let items: Box<dyn Iterator<Item = Result<(usize, String), StdinError>>> = match self.source
+        {
+            Source::Path(path) => {
+                let items = self
+                    .opts
+                    .split(trim.apply(&path.to_string_lossy()), D)
+                    .map(|item| Ok((0, item.to_owned())))
+                    .collect::<Vec<_>>();
+                Box::new(items.into_iter())
+            }
+            source @ (Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_)) => {
+                match source.reader_with(std::any::type_name::<Self>(), Some(&self.opts)) {
+                    Ok(reader) => Box::new(
+                        number_lines(decode_lines(
+                            self.opts
+                                .input_reader(self.opts.progress_reader(reader, None)),
+                            utf8,
+                        ))
+                        .map(move |line| {
+                            line.map(|(n, line)| (n, trim.apply(&line).to_owned()))
+                                .map_err(StdinError::from)
+                        })
+                        .filter(move |line| {
+                            !matches!(line, Ok((_, line)) if line.is_empty() || skipped(line))
+                        }),
+                    ),
+                    Err(e) => Box::new(std::iter::once(Err(e))),
+                }
+            }
+            Source::Arg(value) => {
+                let items = self
+                    .opts
+                    .split(trim.apply(&value), D)
+                    .map(|item| Ok((0, item.to_owned())))
+                    .collect::<Vec<_>>();
+                Box::new(items.into_iter())
+            }
+        };
+        MaybeStdinItems {
+            items,
+            _type: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the parsed items of a [`MaybeStdinIter`]
+pub struct MaybeStdinItems<T> {
+    /// Raw items, with their line number (or `0` for arg values)
+    items: Box<dyn Iterator<Item = Result<(usize, String), StdinError>>>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Iterator for MaybeStdinItems<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    type Item = Result<T, StdinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, item) = match self.items.next()? {
+            Ok(item) => item,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(T::from_str(&item).map_err(|e| match line {
+            0 => StdinError::FromStr(format!("{e}")),
+            line => StdinError::FromStr(format!("line {line}: {e}")),
+        }))
+    }
+}
+
+#[test]
+fn test_iter_arg() {
+    let values: MaybeStdinIter<u32> = " 1,2,3 ".parse().unwrap();
+    assert!(!values.is_stdin());
+    let values = values.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    let parse = MaybeStdinIter::<u32>::with_opts(StdinOpts::new().delimiter(';'));
+    let mut values = parse("1;x").unwrap().into_iter();
+    assert_eq!(values.next().unwrap().unwrap(), 1);
+    assert!(matches!(values.next(), Some(Err(StdinError::FromStr(_)))));
+    assert!(values.next().is_none());
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use super::{SourceKind, StdinError, StdinOpts, Trim};
+
+/// Wrapper struct to parse `KEY=VALUE` pairs from an arg value, or from the lines of `stdin`
+///
+/// Arg values are split on `,` (or the delimiters configured with [`StdinOpts::delimiter`]).
+/// Lines read from `stdin` hold one pair each, in the style of `.env` files: blank lines and
+/// lines starting with `#` are skipped. Keys and values are trimmed before they are parsed, and
+/// a repeated key keeps its last value.
+///
+/// The pairs are collected into a [`HashMap`] by default, or into any map type `M` (E.g. a
+/// [`BTreeMap`](std::collections::BTreeMap) to keep the keys sorted).
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinMap;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     set: MaybeStdinMap<String, String, BTreeMap<String, String>>,
+/// }
+///
+/// let args = Args::parse_from(["example", "--set", "region=eu,replicas=3"]);
+/// assert_eq!(args.set["replicas"], "3");
+/// ```
+///
+/// ```sh
+/// $ ./example --set region=eu,replicas=3
+/// $ cat overrides.env | ./example --set -
+/// ```
+#[derive(Debug, Clone)]
+pub struct MaybeStdinMap<K, V, M = HashMap<K, V>> {
+    inner: M,
+    source: SourceKind,
+    pairs: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V, M> MaybeStdinMap<K, V, M>
+where
+    K: FromStr,
+    K::Err: std::fmt::Display,
+    V: FromStr,
+    V::Err: std::fmt::Display,
+    M: FromIterator<(K, V)>,
+{
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let value = source.get_value_with(std::any::type_name::<Self>(), opts)?;
+        let inner = match kind {
+            SourceKind::Stdin => value
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| {
+                    let line = line.trim();
+                    !line.is_empty() && !line.starts_with('#')
+                })
+                .map(|(i, line)| {
+                    parse_pair(line, opts)
+                        .map_err(|e| StdinError::FromStr(format!("line {}: {e}", i + 1)))
+                })
+                .collect::<Result<M, _>>()?,
+            _ => opts
+                .split(&value, ',')
+                .filter(|pair| !pair.trim().is_empty())
+                .map(|pair| parse_pair(pair, opts).map_err(StdinError::FromStr))
+                .collect::<Result<M, _>>()?,
+        };
+        debug_log!(
+            "{}: resolved to {} ({} bytes)",
+            std::any::type_name::<Self>(),
+            kind.name(),
+            value.len()
+        );
+        Ok(Self {
+            inner,
+            source: kind,
+            pairs: PhantomData,
+        })
+    }
+
+    /// A `value_parser` that reads and splits the pairs as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+}
+
+/// Parse a single `KEY=VALUE` pair
+fn parse_pair<K, V>(pair: &str, opts: &StdinOpts) -> Result<(K, V), String>
+where
+    K: FromStr,
+    K::Err: std::fmt::Display,
+    V: FromStr,
+    V::Err: std::fmt::Display,
+{
+    let trim = opts.trim_or(Trim::Whitespace);
+    let (key, value) = pair
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got {:?}", trim.apply(pair)))?;
+    let (key, value) = (trim.apply(key), trim.apply(value));
+    let parsed_key = K::from_str(key).map_err(|e| format!("key {key:?}: {e}"))?;
+    let parsed_value = V::from_str(value).map_err(|e| format!("value of {key:?}: {e}"))?;
+    Ok((parsed_key, parsed_value))
+}
+
+impl<K, V, M> MaybeStdinMap<K, V, M> {
+    /// Where these pairs originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these pairs read from stdin (shorthand for checking [`MaybeStdinMap::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the map from the wrapper
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+impl<K, V, M> FromStr for MaybeStdinMap<K, V, M>
+where
+    K: FromStr,
+    K::Err: std::fmt::Display,
+    V: FromStr,
+    V::Err: std::fmt::Display,
+    M: FromIterator<(K, V)>,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// With the `clap` feature, clap parses `MaybeStdinMap` args with a [`StdinValueParser`](crate::StdinValueParser),
+/// which names the arg in error messages
+#[cfg(feature = "clap")]
+impl<K, V, M> clap::builder::ValueParserFactory for MaybeStdinMap<K, V, M>
+where
+    K: FromStr + Clone + Send + Sync + 'static,
+    K::Err: std::fmt::Display,
+    V: FromStr + Clone + Send + Sync + 'static,
+    V::Err: std::fmt::Display,
+    M: FromIterator<(K, V)> + Clone + Send + Sync + 'static,
+{
+    type Parser = crate::StdinValueParser<Self>;
+
+    fn value_parser() -> Self::Parser {
+        crate::StdinValueParser::new(<Self as FromStr>::from_str)
+    }
+}
+
+impl<K, V, M> std::ops::Deref for MaybeStdinMap<K, V, M> {
+    type Target = M;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<K, V, M> std::ops::DerefMut for MaybeStdinMap<K, V, M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<K, V, M: IntoIterator> IntoIterator for MaybeStdinMap<K, V, M> {
+    type Item = M::Item;
+    type IntoIter = M::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+#[test]
+fn test_maybe_stdin_map() {
+    let map: MaybeStdinMap<String, u32> = "a=1, b = 2,".parse().unwrap();
+    assert_eq!(map.source_kind(), SourceKind::Literal);
+    assert_eq!(
+        map.into_inner(),
+        HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+    );
+
+    let _session = crate::StdinSession::with_input("# defaults\nA=1\n\nB==2\nA=3\n");
+    let map: MaybeStdinMap<String, String, std::collections::BTreeMap<_, _>> = "-".parse().unwrap();
+    assert!(map.is_stdin());
+    assert_eq!(
+        map.into_iter().collect::<Vec<_>>(),
+        [
+            ("A".to_string(), "3".to_string()),
+            ("B".to_string(), "=2".to_string())
+        ]
+    );
+
+    let _session = crate::StdinSession::with_input("a=1\nb\n");
+    let error = "-".parse::<MaybeStdinMap<String, u32>>().unwrap_err();
+    assert!(matches!(error, StdinError::FromStr(e) if e.starts_with("line 2: expected KEY=VALUE")));
+    assert!("a=x".parse::<MaybeStdinMap<String, u32>>().is_err());
+}
@@ -0,0 +1,163 @@
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{os_string_from_bytes, Source, SourceKind, StdinError, StdinOpts, Trim};
+
+/// Wrapper struct for [`OsString`] arg values (E.g. file names), or the raw contents of `stdin`
+///
+/// Unlike [`MaybeStdin<String>`](crate::MaybeStdin), neither the arg value nor `stdin` has to be
+/// valid UTF-8, so file names that aren't valid UTF-8 on Unix survive intact. The contents of
+/// `stdin` are kept as raw bytes (without their trailing newline); on platforms other than Unix
+/// they must be valid UTF-8.
+///
+/// Arg values that aren't valid UTF-8 are only accepted with the `clap` feature, which lets
+/// clap parse `MaybeStdinOs` from `OsStr` values.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinOs;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     name: MaybeStdinOs,
+/// }
+///
+/// let args = Args::parse();
+/// println!("name={}", args.name.to_string_lossy());
+/// ```
+///
+/// ```sh
+/// $ ./example "$(printf 'caf\xe9.txt')"
+/// $ printf 'caf\xe9.txt' | ./example -
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaybeStdinOs {
+    inner: OsString,
+    source: SourceKind,
+}
+
+impl MaybeStdinOs {
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let inner = match source {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => {
+                let bytes = source.get_bytes_with(std::any::type_name::<Self>(), opts)?;
+                let bytes = opts.trim_or(Trim::TrailingNewline).apply_bytes(&bytes);
+                os_string_from_bytes(bytes.to_vec())?
+            }
+            Source::Arg(value) => OsString::from(value),
+            Source::Path(path) => path.into_os_string(),
+        };
+        Ok(Self {
+            inner,
+            source: kind,
+        })
+    }
+
+    /// A `value_parser` that reads the value as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// Parse an arg value that may not be valid UTF-8, which is always used as is
+    #[cfg(feature = "clap")]
+    fn from_os_string(value: OsString) -> Result<Self, StdinError> {
+        match value.into_string() {
+            Ok(value) => Self::parse_with_opts(&value, &StdinOpts::default()),
+            Err(value) => Ok(Self {
+                inner: value,
+                source: SourceKind::Literal,
+            }),
+        }
+    }
+
+    /// Where this value originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was this value read from stdin (shorthand for checking [`MaybeStdinOs::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the value from the wrapper
+    pub fn into_inner(self) -> OsString {
+        self.inner
+    }
+}
+
+impl FromStr for MaybeStdinOs {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// With the `clap` feature, clap parses `MaybeStdinOs` args from `OsStr` values
+#[cfg(feature = "clap")]
+impl clap::builder::ValueParserFactory for MaybeStdinOs {
+    type Parser = clap::builder::TryMapValueParser<
+        clap::builder::OsStringValueParser,
+        fn(OsString) -> Result<Self, StdinError>,
+    >;
+
+    fn value_parser() -> Self::Parser {
+        use clap::builder::TypedValueParser;
+        clap::builder::OsStringValueParser::new()
+            .try_map(Self::from_os_string as fn(OsString) -> Result<Self, StdinError>)
+    }
+}
+
+impl std::ops::Deref for MaybeStdinOs {
+    type Target = OsString;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl std::ops::DerefMut for MaybeStdinOs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl AsRef<OsStr> for MaybeStdinOs {
+    fn as_ref(&self) -> &OsStr {
+        &self.inner
+    }
+}
+
+impl AsRef<Path> for MaybeStdinOs {
+    fn as_ref(&self) -> &Path {
+        Path::new(&self.inner)
+    }
+}
+
+#[test]
+fn test_maybe_stdin_os() {
+    let value: MaybeStdinOs = "name.txt".parse().unwrap();
+    assert_eq!(value.source_kind(), SourceKind::Literal);
+    assert_eq!(*value, "name.txt");
+
+    let _session = crate::StdinSession::with_input(" name.txt \n");
+    let value: MaybeStdinOs = "-".parse().unwrap();
+    assert!(value.is_stdin());
+    assert_eq!(value.into_inner(), " name.txt ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_maybe_stdin_os_invalid_utf8() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let _session = crate::StdinSession::with_input(*b"caf\xe9.txt\n");
+    let value: MaybeStdinOs = "-".parse().unwrap();
+    assert_eq!(value.as_bytes(), b"caf\xe9.txt");
+}
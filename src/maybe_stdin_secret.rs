@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+use super::guard::claim_stdin;
+use super::session::stdin_is_terminal;
+use super::terminal::prompt_secret;
+use super::{Source, SourceKind, StdinError, StdinOpts, Trim};
+
+/// Wrapper struct for secret arg values (E.g. passwords and tokens), or a secret read from `stdin`
+///
+/// When `stdin` is an interactive terminal, the secret is typed without echo after a prompt
+/// (`Password: `, or the prompt configured with [`StdinOpts::prompt`]). Piped input is read
+/// normally, without its trailing newline.
+///
+/// The secret is never printed by the `Debug` impl, and has to be accessed explicitly with
+/// [`MaybeStdinSecret::expose`].
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::MaybeStdinSecret;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     password: MaybeStdinSecret,
+/// }
+///
+/// let args = Args::parse();
+/// let _password: &str = args.password.expose();
+/// ```
+///
+/// ```sh
+/// $ ./example --password -
+/// Password:
+/// $ pass show example | ./example --password -
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct MaybeStdinSecret {
+    inner: String,
+    source: SourceKind,
+}
+
+impl MaybeStdinSecret {
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let consumer = std::any::type_name::<Self>();
+        let inner = match source {
+            Source::Stdin(_) | Source::SharedStdin(_) if stdin_is_terminal() => {
+                claim_stdin(consumer)?;
+                prompt_secret(opts.prompt.as_deref().unwrap_or("Password: "))?
+            }
+            source => {
+                let value = source.get_value_with(consumer, opts)?;
+                opts.trim_or(Trim::TrailingNewline).apply(&value).to_owned()
+            }
+        };
+        Ok(Self {
+            inner,
+            source: kind,
+        })
+    }
+
+    /// A `value_parser` that reads the secret as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// Where this secret originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was this secret read from stdin (shorthand for checking [`MaybeStdinSecret::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// The secret itself
+    pub fn expose(&self) -> &str {
+        &self.inner
+    }
+
+    /// Extract the secret from the wrapper
+    pub fn into_inner(self) -> String {
+        self.inner
+    }
+}
+
+impl FromStr for MaybeStdinSecret {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+impl std::fmt::Debug for MaybeStdinSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinSecret")
+            .field("source", &self.source)
+            .field("value", &"<redacted>")
+            .finish()
+    }
+}
+
+#[test]
+fn test_maybe_stdin_secret() {
+    let _session = crate::StdinSession::with_input(" hunter2 \n");
+    let secret: MaybeStdinSecret = "-".parse().unwrap();
+    assert!(secret.is_stdin());
+    assert_eq!(secret.expose(), " hunter2 ");
+    assert!(!format!("{secret:?}").contains("hunter2"));
+
+    let secret: MaybeStdinSecret = "token".parse().unwrap();
+    assert_eq!(secret.source_kind(), SourceKind::Literal);
+    assert_eq!(secret.into_inner(), "token");
+}
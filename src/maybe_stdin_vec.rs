@@ -0,0 +1,547 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::str::FromStr;
+use std::time::Instant;
+
+use super::{ReadStats, SourceKind, StdinError, StdinOpts, Trim};
+
+/// The `Display` of the `Default` empty list, parsed back to it
+const EMPTY_DEFAULT: &str = "[]";
+
+/// Wrapper struct to parse a list of arg values, or lines from `stdin`
+///
+/// Arg values are split on the delimiter `D`. The `Default` empty list is displayed as `[]`, which
+/// parses back to it, so it can be the `default_value_t` of an arg. Values read from `stdin` are
+/// split on lines, unless `STDIN_DELIMITED` is set, in which case `stdin` is split on `D` as well,
+/// so the arg and piped forms of the value share one grammar (see [`DelimitedStdinVec`]). For the NUL separated
+/// output of `find -print0`, see [`NulStdinVec`](crate::NulStdinVec).
+#[derive(Clone)]
+pub struct MaybeStdinVec<T, const D: char = ',', const STDIN_DELIMITED: bool = false> {
+    inner: Vec<T>,
+    source: SourceKind,
+    delimiter: char,
+    stats: ReadStats,
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> MaybeStdinVec<T, D, STDIN_DELIMITED> {
+    /// Wrap already parsed items, recording where they originated from
+    ///
+    /// ```rust
+    /// use clap_stdin::{MaybeStdinVec, SourceKind};
+    ///
+    /// let values: MaybeStdinVec<u32> = MaybeStdinVec::from_items(vec![1, 2], SourceKind::Stdin);
+    /// assert!(values.is_stdin());
+    /// ```
+    pub fn from_items(items: Vec<T>, source: SourceKind) -> Self {
+        let stats = ReadStats {
+            items_parsed: items.len(),
+            ..ReadStats::default()
+        };
+        Self {
+            inner: items,
+            source,
+            delimiter: D,
+            stats,
+        }
+    }
+
+    /// Parse the items, reading from stdin as configured by `opts`
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let delimiter = opts.display_delimiter(D);
+        if s == EMPTY_DEFAULT {
+            return Ok(Self {
+                delimiter,
+                ..Self::default()
+            });
+        }
+        let (source, kind) = opts.resolve(s)?;
+        let started = Instant::now();
+        let value = source.get_value_with(std::any::type_name::<Self>(), opts)?;
+        let mut stats = ReadStats {
+            bytes_read: value.len() as u64,
+            duration: started.elapsed(),
+            ..ReadStats::default()
+        };
+        let trim = opts.trim_or(Trim::Whitespace);
+        let raw = value.as_str();
+        let value = trim.apply(raw);
+        let is_stdin = kind == SourceKind::Stdin && !STDIN_DELIMITED;
+        let unit = match opts.record_separator {
+            Some(_) => "record",
+            None => "line",
+        };
+        let items: Box<dyn Iterator<Item = (usize, &str)>> = match &opts.record_separator {
+            Some(separator) if is_stdin => Box::new(
+                value
+                    .split(separator.as_str())
+                    .map(|record| trim.apply(record))
+                    .filter(|record| !record.is_empty())
+                    .enumerate(),
+            ),
+            // The lines are numbered before trimming, so that the leading blank lines trimmed
+            // off the value still count
+            _ if is_stdin => Box::new(
+                raw.lines()
+                    .map(|line| trim.apply(line))
+                    .enumerate()
+                    .skip_while(|(_, line)| trim == Trim::Whitespace && line.is_empty())
+                    .take(value.lines().count()),
+            ),
+            _ => Box::new(opts.split(value, D).enumerate()),
+        };
+        let skipped = opts.line_filter();
+        items
+            .filter(|(_, s)| !(is_stdin && skipped(s)))
+            .map(|(i, s)| {
+                T::from_str(s).map_err(|e| match is_stdin {
+                    true => StdinError::FromStr(format!("{unit} {}: {e}", i + 1)),
+                    false => StdinError::FromStr(format!("{e}")),
+                })
+            })
+            .collect::<Result<Vec<T>, _>>()
+            .map(|inner| {
+                stats.items_parsed = inner.len();
+                debug_log!(
+                    "{}: resolved to {} ({} bytes, {} items)",
+                    std::any::type_name::<Self>(),
+                    kind.name(),
+                    stats.bytes_read,
+                    stats.items_parsed
+                );
+                Self {
+                    inner,
+                    source: kind,
+                    delimiter,
+                    stats,
+                }
+            })
+    }
+
+    /// A `value_parser` that reads and splits the value as configured by `opts`
+    ///
+    /// [`StdinOpts::delimiter`] overrides the const generic delimiter `D`:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdinVec, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = MaybeStdinVec::<u32>::with_opts(StdinOpts::new().delimiter(';')))]
+    ///     values: MaybeStdinVec<u32>,
+    /// }
+    ///
+    /// let args = Args::parse_from(["example", "1;2;3"]);
+    /// assert_eq!(*args.values, [1, 2, 3]);
+    /// ```
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// A `value_parser` that splits arg values on `delimiter`, instead of the const generic
+    /// delimiter `D` (shorthand for [`StdinOpts::delimiter`])
+    ///
+    /// To take the delimiter from another flag (E.g. `--field-sep`), keep the raw arg value and
+    /// parse it once the args are known:
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::MaybeStdinVec;
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(long, default_value_t = ',')]
+    ///     field_sep: char,
+    ///     values: String,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let args = Args::parse_from(["example", "--field-sep", ":", "1:2:3"]);
+    /// let values = MaybeStdinVec::<u32>::with_delimiter(args.field_sep)(&args.values)?;
+    /// assert_eq!(*values, [1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_delimiter(
+        delimiter: char,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        Self::with_opts(StdinOpts::new().delimiter(delimiter))
+    }
+
+    /// A `value_parser` that reads the items as configured by `opts`, and sorts them
+    pub fn sorted(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr + Ord,
+        T::Err: std::fmt::Display,
+    {
+        move |s| {
+            let mut values = Self::parse_with_opts(s, &opts)?;
+            values.inner.sort();
+            Ok(values)
+        }
+    }
+
+    /// A `value_parser` that reads the items as configured by `opts`, sorts them and removes the
+    /// duplicates, like `sort -u`
+    ///
+    /// ```rust
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdinVec, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = MaybeStdinVec::<String>::unique(StdinOpts::new()))]
+    ///     hosts: MaybeStdinVec<String>,
+    /// }
+    ///
+    /// let args = Args::parse_from(["example", "b.com,a.com,b.com"]);
+    /// assert_eq!(*args.hosts, ["a.com", "b.com"]);
+    /// ```
+    pub fn unique(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr + Ord,
+        T::Err: std::fmt::Display,
+    {
+        move |s| {
+            let mut values = Self::parse_with_opts(s, &opts)?;
+            values.inner.sort();
+            values.inner.dedup();
+            Ok(values)
+        }
+    }
+
+    /// A `value_parser` that reads the items as configured by `opts`, and removes the duplicates
+    /// while keeping the first of each in place, like `awk '!seen[$0]++'`
+    ///
+    /// ```rust
+    /// use clap_stdin::{MaybeStdinVec, StdinOpts};
+    ///
+    /// let parse = MaybeStdinVec::<String>::unique_in_order(StdinOpts::new());
+    /// assert_eq!(*parse("b.com,a.com,b.com").unwrap(), ["b.com", "a.com"]);
+    /// ```
+    pub fn unique_in_order(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static
+    where
+        T: FromStr + Eq + Hash,
+        T::Err: std::fmt::Display,
+    {
+        move |s| {
+            let mut values = Self::parse_with_opts(s, &opts)?;
+            let mut seen = HashSet::with_capacity(values.inner.len());
+            let first: Vec<bool> = values.inner.iter().map(|item| seen.insert(item)).collect();
+            let mut first = first.into_iter();
+            values.inner.retain(|_| first.next().unwrap_or(true));
+            Ok(values)
+        }
+    }
+
+    /// Where this value originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Was this value read from stdin (shorthand for checking the `source_kind`)
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Statistics of reading and parsing the items
+    ///
+    /// ```rust
+    /// use clap_stdin::MaybeStdinVec;
+    ///
+    /// let values: MaybeStdinVec<u32> = "1,2,3".parse().unwrap();
+    /// assert_eq!(values.stats().bytes_read, 5);
+    /// assert_eq!(values.stats().items_parsed, 3);
+    /// ```
+    pub fn stats(&self) -> ReadStats {
+        self.stats
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> FromStr for MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// With the `clap` feature, clap parses `MaybeStdinVec` args with a [`StdinValueParser`](crate::StdinValueParser),
+/// which names the arg in error messages
+#[cfg(feature = "clap")]
+impl<T, const D: char, const STDIN_DELIMITED: bool> clap::builder::ValueParserFactory
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    T::Err: std::fmt::Display,
+{
+    type Parser = crate::StdinValueParser<Self>;
+
+    fn value_parser() -> Self::Parser {
+        crate::StdinValueParser::new(<Self as FromStr>::from_str)
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    /// Parse each of the already split values, failing on the first invalid one
+    ///
+    /// This replaces `FromIterator<String>`, which couldn't report the error without panicking.
+    ///
+    /// ```rust
+    /// use clap_stdin::MaybeStdinVec;
+    ///
+    /// let values = MaybeStdinVec::<u32>::try_from_iter(["1", "2"]).unwrap();
+    /// assert_eq!(values.into_inner(), vec![1, 2]);
+    /// assert!(MaybeStdinVec::<u32>::try_from_iter(["1", "two"]).is_err());
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, StdinError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        iter.into_iter()
+            .map(|s| T::from_str(s.as_ref()).map_err(|e| StdinError::FromStr(format!("{e}"))))
+            .collect::<Result<Vec<T>, _>>()
+            .map(|inner| Self::from_items(inner, SourceKind::Literal))
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> TryFrom<Vec<String>>
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    type Error = StdinError;
+
+    fn try_from(values: Vec<String>) -> Result<Self, Self::Error> {
+        Self::try_from_iter(values)
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> MaybeStdinVec<T, D, STDIN_DELIMITED> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+/// No items, with [`SourceKind::Default`]
+///
+/// A `default_value_t` arg is parsed from the `Display` of this value instead, as an empty
+/// [`SourceKind::Literal`] list.
+///
+/// Build values from items with [`MaybeStdinVec::from_items`], since `From<Vec<T>>` would conflict
+/// with `TryFrom<Vec<String>>`.
+impl<T, const D: char, const STDIN_DELIMITED: bool> Default
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+{
+    fn default() -> Self {
+        Self::from_items(Vec::new(), SourceKind::Default)
+    }
+}
+
+/// Joins the items with the delimiter `D`, or with newlines if the items were read from stdin
+/// (and split on lines). The `Default` empty list is displayed as `[]`
+impl<T, const D: char, const STDIN_DELIMITED: bool> std::fmt::Display
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.source == SourceKind::Default && self.inner.is_empty() {
+            return f.write_str(EMPTY_DEFAULT);
+        }
+        let delimiter = if self.is_stdin() && !STDIN_DELIMITED {
+            '\n'
+        } else {
+            self.delimiter
+        };
+        for (i, item) in self.inner.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{delimiter}")?;
+            }
+            item.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> std::fmt::Debug
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaybeStdinVec")
+            .field("source", &self.source)
+            .field("delimiter", &self.delimiter)
+            .field("value", &self.inner)
+            .finish()
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> std::ops::Deref
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+{
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, const D: char, const STDIN_DELIMITED: bool> std::ops::DerefMut
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+/// A [`MaybeStdinVec`] that splits `stdin` on the delimiter `D` too, instead of on lines
+///
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::DelimitedStdinVec;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     ids: DelimitedStdinVec<u32>,
+/// }
+///
+/// let args = Args::parse_from(["example", "1,2,3"]);
+/// assert_eq!(*args.ids, [1, 2, 3]);
+/// ```
+///
+/// ```sh
+/// $ echo "1,2,3" | ./example -
+/// ```
+pub type DelimitedStdinVec<T, const D: char = ','> = MaybeStdinVec<T, D, true>;
+
+#[test]
+fn test_with_delimiter() {
+    let parse = MaybeStdinVec::<u32>::with_delimiter(';');
+    let values = parse("1;2").unwrap();
+    assert_eq!(*values, [1, 2]);
+    assert_eq!(values.to_string(), "1;2");
+    assert!(parse("1,2").is_err());
+}
+
+#[test]
+fn test_stdin_lines_trim() {
+    let _session = crate::StdinSession::with_input(" 1 \n\t2\n");
+    let values: MaybeStdinVec<u32> = "-".parse().unwrap();
+    assert_eq!(*values, [1, 2]);
+
+    let _session = crate::StdinSession::with_input("  indented\nplain  \n");
+    let parse = MaybeStdinVec::<String>::with_opts(StdinOpts::new().trim(Trim::TrailingNewline));
+    assert_eq!(*parse("-").unwrap(), ["  indented", "plain  "]);
+}
+
+#[test]
+fn test_vec_display() {
+    let values: MaybeStdinVec<u32, '-'> = "1-2-3".parse().unwrap();
+    assert_eq!(values.to_string(), "1-2-3");
+
+    let values: MaybeStdinVec<u32> = MaybeStdinVec::from_items(vec![1, 2, 3], SourceKind::Stdin);
+    assert_eq!(values.to_string(), "1\n2\n3");
+
+    let values: MaybeStdinVec<u32> = MaybeStdinVec::from_items(vec![], SourceKind::Literal);
+    assert_eq!(values.to_string(), "");
+
+    let values: DelimitedStdinVec<u32> =
+        DelimitedStdinVec::from_items(vec![1, 2], SourceKind::Stdin);
+    assert_eq!(values.to_string(), "1,2");
+}
+
+#[test]
+fn test_try_from_vec() {
+    let values = MaybeStdinVec::<u32>::try_from(vec!["1".to_owned(), "2".to_owned()]).unwrap();
+    assert_eq!(values.source_kind(), SourceKind::Literal);
+    assert_eq!(*values, [1, 2]);
+
+    let error = MaybeStdinVec::<u32>::try_from(vec!["one".to_owned()]).unwrap_err();
+    assert!(matches!(error, StdinError::FromStr(_)));
+}
+
+#[test]
+fn test_skip_comments() {
+    let opts = StdinOpts::new().skip_blank().comment_prefix("#");
+    let _session = crate::StdinSession::with_input("# sizes\n1\n\n  # more\n2\nthree\n");
+    let error = MaybeStdinVec::<u32>::with_opts(opts.clone())("-").unwrap_err();
+    assert!(matches!(error, StdinError::FromStr(e) if e.starts_with("line 6:")));
+
+    let values = MaybeStdinVec::<String>::with_opts(opts)("#1,2").unwrap();
+    assert_eq!(*values, ["#1", "2"]);
+
+    // Leading blank lines are trimmed off, but still counted
+    let _session = crate::StdinSession::with_input("\n \n1\nabc\n\n");
+    let error = "-".parse::<MaybeStdinVec<u32>>().unwrap_err();
+    assert!(matches!(error, StdinError::FromStr(e) if e.starts_with("line 4:")));
+}
+
+#[test]
+fn test_sorted_unique() {
+    assert_eq!(
+        *MaybeStdinVec::<u32>::sorted(StdinOpts::new())("3,1,3,2").unwrap(),
+        [1, 2, 3, 3]
+    );
+
+    let _session = crate::StdinSession::with_input("b\na\nb\n");
+    let values = MaybeStdinVec::<String>::unique(StdinOpts::new())("-").unwrap();
+    assert!(values.is_stdin());
+    assert_eq!(*values, ["a", "b"]);
+
+    let opts = StdinOpts::new().delimiter(';');
+    let values = MaybeStdinVec::<u32>::unique_in_order(opts)("3;1;3;2;1").unwrap();
+    assert_eq!(*values, [3, 1, 2]);
+}
+
+#[test]
+fn test_default_value_t() {
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct Args {
+        #[arg(long, default_value_t)]
+        ports: MaybeStdinVec<u16>,
+    }
+
+    let args = Args::try_parse_from(["example"]).unwrap();
+    assert!(args.ports.is_empty());
+    assert_eq!(args.ports.source_kind(), SourceKind::Default);
+    assert_eq!(args.ports.to_string(), "[]");
+    // An explicit empty value is still parsed as an item
+    assert!(Args::try_parse_from(["example", "--ports", ""]).is_err());
+    let args = Args::try_parse_from(["example", "--ports", "80,443"]).unwrap();
+    assert_eq!(*args.ports, [80, 443]);
+
+    let tags = MaybeStdinVec::<String>::from_str("").unwrap();
+    assert_eq!(*tags, [""]);
+}
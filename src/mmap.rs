@@ -0,0 +1,81 @@
+//! Memory-mapped contents of a [`FileOrStdin`](crate::FileOrStdin), see
+//! [`FileOrStdin::mmap`](crate::FileOrStdin::mmap)
+use std::fs::File;
+use std::ops::Deref;
+
+use super::opts::max_bytes_limit;
+use super::{StdinError, StdinOpts};
+
+/// The raw contents of a [`FileOrStdin`](crate::FileOrStdin): a read-only memory map of a file,
+/// or the contents of `stdin` read into a buffer
+///
+/// Derefs to the bytes of the input, as is.
+pub struct MappedContents {
+    inner: Inner,
+}
+
+enum Inner {
+    Mapped(memmap2::Mmap),
+    Buffered(Vec<u8>),
+}
+
+impl MappedContents {
+    /// Map the file into memory, failing if it's larger than [`StdinOpts::max_bytes`] (or
+    /// [`MAX_BYTES_ENV`](crate::MAX_BYTES_ENV))
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the map is alive, see
+    /// [`FileOrStdin::mmap`](crate::FileOrStdin::mmap).
+    pub(crate) unsafe fn map(file: &File, opts: &StdinOpts) -> Result<Self, StdinError> {
+        if let Some(max_bytes) = max_bytes_limit(Some(opts))? {
+            if file.metadata()?.len() > max_bytes {
+                return Err(StdinError::InputTooLarge(max_bytes));
+            }
+        }
+        // SAFETY: the map is read-only, and the caller guarantees the file isn't modified or
+        // truncated while it's mapped
+        let map = unsafe { memmap2::Mmap::map(file)? };
+        Ok(Self {
+            inner: Inner::Mapped(map),
+        })
+    }
+
+    /// Contents read into a buffer, E.g. from `stdin`
+    pub(crate) fn buffered(bytes: Vec<u8>) -> Self {
+        Self {
+            inner: Inner::Buffered(bytes),
+        }
+    }
+
+    /// Were the contents mapped from a file (rather than read into a buffer)
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.inner, Inner::Mapped(_))
+    }
+}
+
+impl Deref for MappedContents {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match &self.inner {
+            Inner::Mapped(map) => map,
+            Inner::Buffered(bytes) => bytes,
+        }
+    }
+}
+
+impl AsRef<[u8]> for MappedContents {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl std::fmt::Debug for MappedContents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedContents")
+            .field("mapped", &self.is_mapped())
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,182 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::{os_string_from_bytes, Source, SourceKind, StdinError, StdinOpts, Trim};
+
+/// Wrapper struct to read NUL separated paths from `stdin`, for the output of `find -print0`
+///
+/// `stdin` is split on NUL bytes, and each item is kept as is: file names can contain newlines,
+/// surrounding whitespace, or (on Unix) bytes that aren't valid UTF-8. The NUL terminating the
+/// last item is ignored. An arg value is a single item.
+///
+/// Items are built from an [`OsString`], so `T` is a [`PathBuf`] (the default) or an
+/// [`OsString`].
+/// ```rust
+/// use std::path::PathBuf;
+/// use clap::Parser;
+/// use clap_stdin::NulStdinVec;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     files: NulStdinVec,
+/// }
+///
+/// let args = Args::parse_from(["example", "a, b.txt"]);
+/// assert_eq!(*args.files, [PathBuf::from("a, b.txt")]);
+/// ```
+///
+/// ```sh
+/// $ find . -name '*.txt' -print0 | ./example -
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NulStdinVec<T = PathBuf> {
+    inner: Vec<T>,
+    source: SourceKind,
+}
+
+impl<T: From<OsString>> NulStdinVec<T> {
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let (source, kind) = opts.resolve(s)?;
+        let inner = match source {
+            Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_) => {
+                let bytes = source.get_bytes_with(std::any::type_name::<Self>(), opts)?;
+                let bytes = bytes.strip_suffix(b"\0").unwrap_or(&bytes);
+                match bytes.is_empty() {
+                    true => Vec::new(),
+                    false => bytes
+                        .split(|b| *b == b'\0')
+                        .map(|item| opts.trim_or(Trim::None).apply_bytes(item).to_vec())
+                        .map(|item| os_string_from_bytes(item).map(T::from))
+                        .collect::<Result<_, _>>()?,
+                }
+            }
+            Source::Arg(value) => vec![T::from(OsString::from(value))],
+            Source::Path(path) => vec![T::from(path.into_os_string())],
+        };
+        debug_log!(
+            "{}: resolved to {} ({} items)",
+            std::any::type_name::<Self>(),
+            kind.name(),
+            inner.len()
+        );
+        Ok(Self {
+            inner,
+            source: kind,
+        })
+    }
+
+    /// A `value_parser` that reads the items as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    /// Parse an arg value that may not be valid UTF-8, which is always a single item
+    #[cfg(feature = "clap")]
+    fn from_os_string(value: OsString) -> Result<Self, StdinError> {
+        match value.into_string() {
+            Ok(value) => Self::parse_with_opts(&value, &StdinOpts::default()),
+            Err(value) => Ok(Self {
+                inner: vec![T::from(value)],
+                source: SourceKind::Literal,
+            }),
+        }
+    }
+}
+
+impl<T> NulStdinVec<T> {
+    /// Where these items originated from
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these items read from stdin (shorthand for checking [`NulStdinVec::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the items from the wrapper
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T: From<OsString>> FromStr for NulStdinVec<T> {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+/// With the `clap` feature, clap parses `NulStdinVec` args from `OsStr` values
+#[cfg(feature = "clap")]
+impl<T> clap::builder::ValueParserFactory for NulStdinVec<T>
+where
+    T: From<OsString> + Clone + Send + Sync + 'static,
+{
+    type Parser = clap::builder::TryMapValueParser<
+        clap::builder::OsStringValueParser,
+        fn(OsString) -> Result<Self, StdinError>,
+    >;
+
+    fn value_parser() -> Self::Parser {
+        use clap::builder::TypedValueParser;
+        clap::builder::OsStringValueParser::new()
+            .try_map(Self::from_os_string as fn(OsString) -> Result<Self, StdinError>)
+    }
+}
+
+impl<T> std::ops::Deref for NulStdinVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for NulStdinVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> IntoIterator for NulStdinVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+#[test]
+fn test_nul_stdin_vec() {
+    let _session = crate::StdinSession::with_input(" leading\0multi\nline\0last \0");
+    let values: NulStdinVec<OsString> = "-".parse().unwrap();
+    assert!(values.is_stdin());
+    assert_eq!(*values, [" leading", "multi\nline", "last "]);
+
+    let _session = crate::StdinSession::with_input("\0");
+    let values: NulStdinVec = "-".parse().unwrap();
+    assert!(values.is_empty());
+
+    let values: NulStdinVec = "a, b.txt".parse().unwrap();
+    assert_eq!(values.source_kind(), SourceKind::Literal);
+    assert_eq!(*values, [PathBuf::from("a, b.txt")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_nul_stdin_vec_non_utf8() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let _session = crate::StdinSession::with_input(&b"caf\xe9.txt\0b.txt\0"[..]);
+    let values: NulStdinVec = "-".parse().unwrap();
+    assert_eq!(values[0].as_os_str().as_bytes(), b"caf\xe9.txt");
+    assert_eq!(values[1], PathBuf::from("b.txt"));
+}
@@ -0,0 +1,1057 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::bom::strip_bom;
+#[cfg(feature = "vec")]
+use super::bom::SkipBom;
+use super::guard::claim_stdin;
+use super::progress::ProgressCallback;
+use super::session::{stdin_is_terminal, wait_for_stdin};
+use super::terminal::read_stdin_multiline;
+#[cfg(any(feature = "sha2", feature = "blake3"))]
+use super::DigestAlgorithm;
+#[cfg(any(feature = "maybe-stdin", feature = "from-source", feature = "vec"))]
+use super::SourceKind;
+#[cfg(feature = "file")]
+use super::RATE_LIMIT_ENV;
+use super::{BoxedReader, StdinError, MAX_BYTES_ENV, TIMEOUT_ENV};
+#[cfg(any(
+    feature = "maybe-stdin",
+    feature = "from-source",
+    feature = "vec",
+    feature = "file",
+    feature = "output"
+))]
+use super::{SharedStdin, Source};
+#[cfg(feature = "encoding")]
+use encoding_rs::Encoding;
+
+/// How values are trimmed before they are parsed
+///
+/// Configured per arg with [`StdinOpts::trim`]. Otherwise each type applies its usual policy:
+/// - [`Trim::Whitespace`]: [`MaybeStdin`](crate::MaybeStdin), [`LazyMaybeStdin`](crate::LazyMaybeStdin),
+///   [`Fallback`](crate::Fallback), and each item of [`MaybeStdinVec`](crate::MaybeStdinVec),
+///   [`MaybeStdinIter`](crate::MaybeStdinIter) and [`FileOrStdinVec`](crate::FileOrStdinVec)
+/// - [`Trim::End`]: the contents of [`FileOrStdin`](crate::FileOrStdin)
+/// - [`Trim::TrailingNewline`]: [`MaybeStdinOs`](crate::MaybeStdinOs) and
+///   [`MaybeStdinSecret`](crate::MaybeStdinSecret)
+/// - [`Trim::None`]: the items of [`NulStdinVec`](crate::NulStdinVec)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Trim {
+    /// Keep the value as is
+    None,
+    /// Remove a single trailing line ending (`\n` or `\r\n`), as added by `echo`
+    TrailingNewline,
+    /// Remove all trailing whitespace
+    End,
+    /// Remove all leading and trailing whitespace
+    #[default]
+    Whitespace,
+}
+
+impl Trim {
+    /// Apply the trimming policy to `value`
+    pub fn apply(self, value: &str) -> &str {
+        match self {
+            Trim::None => value,
+            Trim::TrailingNewline => value
+                .strip_suffix('\n')
+                .map(|v| v.strip_suffix('\r').unwrap_or(v))
+                .unwrap_or(value),
+            Trim::End => value.trim_end(),
+            Trim::Whitespace => value.trim(),
+        }
+    }
+
+    /// Apply the trimming policy to raw bytes, with ASCII whitespace
+    #[cfg(any(feature = "maybe-stdin", feature = "vec"))]
+    pub(crate) fn apply_bytes(self, value: &[u8]) -> &[u8] {
+        match self {
+            Trim::None => value,
+            Trim::TrailingNewline => value
+                .strip_suffix(b"\n")
+                .map(|v| v.strip_suffix(b"\r").unwrap_or(v))
+                .unwrap_or(value),
+            Trim::End => value.trim_ascii_end(),
+            Trim::Whitespace => value.trim_ascii(),
+        }
+    }
+}
+
+/// How invalid UTF-8 in text input is handled
+///
+/// Applies to every path producing text: the values of [`MaybeStdin`](crate::MaybeStdin),
+/// [`MaybeStdinVec`](crate::MaybeStdinVec) and [`FileOrStdin::contents`](crate::FileOrStdin::contents),
+/// lines and records read from `stdin`, and the structured (E.g. JSON) wrappers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Utf8Policy {
+    /// Fail with an [`InvalidData`](std::io::ErrorKind::InvalidData) error
+    #[default]
+    Strict,
+    /// Replace invalid sequences with `U+FFFD`
+    Lossy,
+}
+
+static LOSSY_UTF8: AtomicBool = AtomicBool::new(false);
+
+impl Utf8Policy {
+    /// Set the crate default policy, used by args without one configured with [`StdinOpts::utf8`]
+    ///
+    #[cfg_attr(feature = "maybe-stdin", doc = "```rust")]
+    #[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+    /// use clap_stdin::{MaybeStdin, Utf8Policy};
+    ///
+    /// Utf8Policy::Lossy.set_default();
+    /// assert_eq!(Utf8Policy::current_default(), Utf8Policy::Lossy);
+    /// ```
+    pub fn set_default(self) {
+        LOSSY_UTF8.store(self == Utf8Policy::Lossy, Ordering::SeqCst);
+    }
+
+    /// The crate default policy (strict, unless changed with [`Utf8Policy::set_default`])
+    pub fn current_default() -> Self {
+        match LOSSY_UTF8.load(Ordering::SeqCst) {
+            true => Utf8Policy::Lossy,
+            false => Utf8Policy::Strict,
+        }
+    }
+
+    /// Decode `bytes` according to the policy
+    pub(crate) fn decode(self, bytes: Vec<u8>) -> io::Result<String> {
+        match self {
+            Utf8Policy::Strict => {
+                String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Utf8Policy::Lossy => Ok(match String::from_utf8(bytes) {
+                Ok(value) => value,
+                Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+            }),
+        }
+    }
+}
+
+/// Per-arg configuration of how values are read, for use with the `with_opts` value parsers
+///
+#[cfg_attr(feature = "maybe-stdin", doc = "```rust")]
+#[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+/// use clap::Parser;
+/// use clap_stdin::{MaybeStdin, StdinOpts, Trim};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(value_parser = MaybeStdin::<String>::with_opts(
+///         StdinOpts::new().sentinel("@stdin").trim(Trim::TrailingNewline).max_bytes(1024)
+///     ))]
+///     value: MaybeStdin<String>,
+/// }
+///
+/// let args = Args::parse_from(["example", "-"]);
+/// assert_eq!(*args.value, "-");
+/// assert!(!args.value.is_stdin());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdinOpts {
+    pub(crate) sentinel: Option<String>,
+    pub(crate) env_prefix: Option<String>,
+    pub(crate) trim: Option<Trim>,
+    pub(crate) max_bytes: Option<u64>,
+    pub(crate) share_stdin: bool,
+    pub(crate) delimiters: Vec<char>,
+    pub(crate) split_whitespace: bool,
+    pub(crate) rate_limit: Option<u64>,
+    pub(crate) record_separator: Option<String>,
+    pub(crate) skip_blank: bool,
+    pub(crate) comment_prefix: Option<String>,
+    pub(crate) literal_fallback: bool,
+    pub(crate) utf8: Option<Utf8Policy>,
+    pub(crate) normalize_newlines: bool,
+    pub(crate) keep_bom: bool,
+    pub(crate) allow_terminal: bool,
+    pub(crate) prompt: Option<String>,
+    pub(crate) terminator: Option<String>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) progress: Option<ProgressCallback>,
+    pub(crate) validator: Option<Validator>,
+    #[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+    pub(crate) decompress: bool,
+    #[cfg(feature = "encoding")]
+    pub(crate) detect_encoding: bool,
+    #[cfg(feature = "encoding")]
+    pub(crate) encoding: Option<&'static Encoding>,
+    #[cfg(any(feature = "sha2", feature = "blake3"))]
+    pub(crate) digest: Option<DigestAlgorithm>,
+}
+
+impl StdinOpts {
+    /// Default options: `-` reads from stdin, and each type applies its usual trimming
+    pub fn new() -> Self {
+        Self {
+            sentinel: Some(String::from("-")),
+            env_prefix: None,
+            trim: None,
+            max_bytes: None,
+            share_stdin: false,
+            delimiters: Vec::new(),
+            split_whitespace: false,
+            rate_limit: None,
+            record_separator: None,
+            skip_blank: false,
+            comment_prefix: None,
+            literal_fallback: false,
+            utf8: None,
+            normalize_newlines: false,
+            keep_bom: false,
+            allow_terminal: false,
+            prompt: None,
+            terminator: None,
+            timeout: None,
+            progress: None,
+            validator: None,
+            #[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+            decompress: false,
+            #[cfg(feature = "encoding")]
+            detect_encoding: false,
+            #[cfg(feature = "encoding")]
+            encoding: None,
+            #[cfg(any(feature = "sha2", feature = "blake3"))]
+            digest: None,
+        }
+    }
+
+    /// Read from stdin when the arg value is `sentinel` (instead of `-`)
+    pub fn sentinel(mut self, sentinel: impl Into<String>) -> Self {
+        self.sentinel = Some(sentinel.into());
+        self
+    }
+
+    /// Never read from stdin: every arg value is used as is
+    ///
+    /// To pass a single literal `-` without disabling stdin, users can escape it as `\-`
+    pub fn no_sentinel(mut self) -> Self {
+        self.sentinel = None;
+        self
+    }
+
+    /// Read the value from an environment variable when the arg value is `prefix` followed by the
+    /// variable name, E.g. `env:API_TOKEN` with the prefix `"env:"`
+    ///
+    /// Keeps secrets out of the command line (and `ps` output) where piping them isn't convenient,
+    /// like in CI. Applies to [`MaybeStdin`](crate::MaybeStdin) and the other value wrappers, which
+    /// report [`SourceKind::Env`](crate::SourceKind::Env). Fails with [`StdinError::EnvNotSet`]
+    /// if the variable isn't set, and with [`StdinError::EnvNotUnicode`] if its value isn't valid
+    /// UTF-8.
+    #[cfg_attr(feature = "maybe-stdin", doc = "```rust")]
+    #[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+    /// use clap_stdin::{MaybeStdin, SourceKind, StdinOpts};
+    ///
+    /// # std::env::set_var("API_TOKEN", "s3cr3t");
+    /// let parse = MaybeStdin::<String>::with_opts(StdinOpts::new().env_prefix("env:"));
+    /// let token = parse("env:API_TOKEN").unwrap();
+    /// assert_eq!(token.source_kind(), SourceKind::Env("API_TOKEN".into()));
+    /// assert_eq!(*token, "s3cr3t");
+    /// ```
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Trim values according to `trim` before parsing them
+    pub fn trim(mut self, trim: Trim) -> Self {
+        self.trim = Some(trim);
+        self
+    }
+
+    /// Fail with [`StdinError::InputTooLarge`](crate::StdinError::InputTooLarge) when reading
+    /// more than `max_bytes` from stdin or a file
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Keep the contents of `stdin` once read by a parsed [`Source`](crate::Source) or
+    /// [`FileOrStdin`](crate::FileOrStdin), so its clones (E.g. made by clap) can read them
+    /// again instead of failing with
+    /// [`StdinError::StdInRepeatedUse`](crate::StdinError::StdInRepeatedUse)
+    ///
+    /// The contents are then read into memory at once, see [`SharedStdin`](crate::SharedStdin).
+    pub fn share_stdin(mut self) -> Self {
+        self.share_stdin = true;
+        self
+    }
+
+    /// Split arg values of [`MaybeStdinVec`](crate::MaybeStdinVec) on `delimiter`,
+    /// instead of its const generic delimiter
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiters = vec![delimiter];
+        self
+    }
+
+    /// Split arg values of [`MaybeStdinVec`](crate::MaybeStdinVec) on any of `delimiters`
+    ///
+    /// Useful for input pasted from spreadsheets and documents, which often mixes separators.
+    /// Empty items between adjacent delimiters are skipped.
+    #[cfg_attr(feature = "vec", doc = "```rust")]
+    #[cfg_attr(not(feature = "vec"), doc = "```ignore")]
+    /// use clap_stdin::{MaybeStdinVec, StdinOpts};
+    ///
+    /// let parse = MaybeStdinVec::<u32>::with_opts(StdinOpts::new().delimiters([',', ';']).split_whitespace());
+    /// assert_eq!(*parse("1, 2;3\t4").unwrap(), [1, 2, 3, 4]);
+    /// ```
+    pub fn delimiters(mut self, delimiters: impl IntoIterator<Item = char>) -> Self {
+        self.delimiters = delimiters.into_iter().collect();
+        self
+    }
+
+    /// Also split arg values of [`MaybeStdinVec`](crate::MaybeStdinVec) on any whitespace,
+    /// skipping empty items
+    pub fn split_whitespace(mut self) -> Self {
+        self.split_whitespace = true;
+        self
+    }
+
+    /// Split values of [`MaybeStdinVec`](crate::MaybeStdinVec) read from stdin on `separator`,
+    /// instead of on lines
+    ///
+    /// The separator can span multiple bytes (E.g. `"\x1e"` for RFC 7464 JSON text sequences,
+    /// or `"\n---\n"` for YAML multi-document streams). Empty records are skipped.
+    pub fn record_separator(mut self, separator: impl Into<String>) -> Self {
+        self.record_separator = Some(separator.into());
+        self
+    }
+
+    /// Skip blank lines of the input read line by line: the lines of
+    /// [`MaybeStdinVec`](crate::MaybeStdinVec) and [`MaybeStdinIter`](crate::MaybeStdinIter)
+    /// values read from stdin, [`FileOrStdin::lines`](crate::FileOrStdin::lines) and
+    /// [`Stdin::lines_with`](crate::Stdin::lines_with)
+    pub fn skip_blank(mut self) -> Self {
+        self.skip_blank = true;
+        self
+    }
+
+    /// Skip the lines starting with `prefix` (after leading whitespace), E.g. `"#"`, of the same
+    /// inputs as [`StdinOpts::skip_blank`]
+    ///
+    /// Comment lines are skipped on their own, without `skip_blank`. Line numbers in error
+    /// messages still count the skipped lines:
+    #[cfg_attr(feature = "vec", doc = "```rust")]
+    #[cfg_attr(not(feature = "vec"), doc = "```ignore")]
+    /// use clap_stdin::{MaybeStdinVec, StdinOpts, StdinSession};
+    ///
+    /// let _session = StdinSession::with_input("# hosts\nexample.com\n\n  # staging\nexample.org\n");
+    /// let parse = MaybeStdinVec::<String>::with_opts(StdinOpts::new().skip_blank().comment_prefix("#"));
+    /// assert_eq!(*parse("-").unwrap(), ["example.com", "example.org"]);
+    /// ```
+    pub fn comment_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.comment_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Predicate of the lines skipped as configured by [`StdinOpts::skip_blank`] and
+    /// [`StdinOpts::comment_prefix`]
+    pub(crate) fn line_filter(&self) -> impl Fn(&str) -> bool + Clone + Send + Sync + 'static {
+        let skip_blank = self.skip_blank;
+        let comment_prefix = self.comment_prefix.clone();
+        move |line| {
+            let line = line.trim_start();
+            (skip_blank && line.is_empty())
+                || comment_prefix
+                    .as_deref()
+                    .is_some_and(|prefix| line.starts_with(prefix))
+        }
+    }
+
+    /// Treat [`FileOrStdin`](crate::FileOrStdin) values that aren't an existing file as the
+    /// contents themselves, instead of failing to open them
+    ///
+    /// For "path or inline value" args; the resolution is reported by `source_kind`:
+    #[cfg_attr(feature = "file", doc = "```rust")]
+    #[cfg_attr(not(feature = "file"), doc = "```ignore")]
+    /// use clap_stdin::{FileOrStdin, SourceKind, StdinOpts};
+    ///
+    /// let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().literal_fallback());
+    /// let query = parse("SELECT 1").unwrap();
+    /// assert_eq!(query.source_kind(), SourceKind::Literal);
+    /// assert_eq!(query.contents().unwrap(), "SELECT 1");
+    /// ```
+    pub fn literal_fallback(mut self) -> Self {
+        self.literal_fallback = true;
+        self
+    }
+
+    /// Throttle reading from stdin or a file to at most `bytes_per_second` (at least 1)
+    ///
+    /// Applies to the readers of [`FileOrStdin`](crate::FileOrStdin), e.g. to test how downstream
+    /// pipelines handle backpressure
+    pub fn rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.rate_limit = Some(bytes_per_second.max(1));
+        self
+    }
+
+    /// Compute a digest of the input while reading it through [`FileOrStdin`](crate::FileOrStdin),
+    /// available afterwards as [`ReadStats::digest`](crate::ReadStats::digest)
+    ///
+    /// Tools that record the hash of their input don't have to read it twice:
+    /// ```rust
+    /// # #[cfg(feature = "sha2")]
+    /// # fn main() -> anyhow::Result<()> {
+    /// use clap_stdin::{DigestAlgorithm, FileOrStdin, StdinOpts};
+    ///
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "abc")?;
+    /// # let path = tmp.path().to_str().unwrap();
+    /// let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().digest(DigestAlgorithm::Sha256));
+    /// let (contents, stats) = parse(path)?.contents_with_stats()?;
+    /// assert_eq!(contents, "abc");
+    /// assert_eq!(stats.digest.unwrap()[..4], [0xba, 0x78, 0x16, 0xbf]);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "sha2"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(any(feature = "sha2", feature = "blake3"))]
+    pub fn digest(mut self, algorithm: DigestAlgorithm) -> Self {
+        self.digest = Some(algorithm);
+        self
+    }
+
+    /// Decompress gzip, zstd or bzip2 input read through [`FileOrStdin`](crate::FileOrStdin),
+    /// recognized by its magic bytes (uncompressed input is read as is)
+    ///
+    /// Each format requires its feature (`gzip`, `zstd` or `bzip2`). [`StdinOpts::max_bytes`]
+    /// and the [`ReadStats`](crate::ReadStats) apply to the decompressed contents.
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_stdin::{FileOrStdin, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = FileOrStdin::<String>::with_opts(StdinOpts::new().decompress()))]
+    ///     input: FileOrStdin,
+    /// }
+    /// ```
+    ///
+    /// ```sh
+    /// $ ./example access.log.gz
+    /// $ zstd -c access.log | ./example -
+    /// ```
+    #[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+    pub fn decompress(mut self) -> Self {
+        self.decompress = true;
+        self
+    }
+
+    /// Transcode text input to UTF-8 when it starts with a byte order mark (UTF-8, UTF-16LE or
+    /// UTF-16BE), which is removed. Input without one is read as is.
+    ///
+    /// Applies to `stdin` read by the value parsers, and to the input of
+    /// [`FileOrStdin`](crate::FileOrStdin), before the values are parsed or deserialized.
+    /// Requires the `encoding` feature.
+    #[cfg(feature = "encoding")]
+    pub fn detect_encoding(mut self) -> Self {
+        self.detect_encoding = true;
+        self
+    }
+
+    /// Transcode text input from `encoding` to UTF-8 (E.g. from Windows-1252), unless it starts
+    /// with a byte order mark indicating another encoding
+    ///
+    /// Like [`StdinOpts::detect_encoding`], with a fallback for input without a byte order mark.
+    /// [`StdinOpts::max_bytes`] and the [`ReadStats`](crate::ReadStats) apply to the transcoded
+    /// contents.
+    /// ```rust
+    /// use clap_stdin::{Encoding, FileOrStdin, StdinOpts};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, b"caf\xe9")?;
+    /// let encoding = Encoding::for_label(b"windows-1252").unwrap();
+    /// let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().encoding(encoding));
+    /// let input = parse(tmp.path().to_str().unwrap())?;
+    /// assert_eq!(input.contents()?, "caf\u{e9}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "encoding")]
+    pub fn encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Handle invalid UTF-8 according to `policy`, instead of the crate default
+    /// (see [`Utf8Policy::set_default`])
+    ///
+    #[cfg_attr(feature = "file", doc = "```rust")]
+    #[cfg_attr(not(feature = "file"), doc = "```ignore")]
+    /// use clap_stdin::{FileOrStdin, StdinOpts, Utf8Policy};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, b"caf\xe9")?;
+    /// let parse = FileOrStdin::<String>::with_opts(StdinOpts::new().utf8(Utf8Policy::Lossy));
+    /// let input = parse(tmp.path().to_str().unwrap())?;
+    /// assert_eq!(input.contents()?, "caf\u{FFFD}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn utf8(mut self, policy: Utf8Policy) -> Self {
+        self.utf8 = Some(policy);
+        self
+    }
+
+    /// Convert Windows line endings (`\r\n`) to `\n` in text read from `stdin` or a file, before
+    /// the value is trimmed, split or parsed
+    ///
+    /// Applies to [`MaybeStdin`](crate::MaybeStdin), [`MaybeStdinVec`](crate::MaybeStdinVec) and
+    /// [`FileOrStdin::contents`](crate::FileOrStdin::contents). Lines (E.g. of
+    /// [`FileOrStdin::lines`](crate::FileOrStdin::lines)) never include their `\r\n` ending.
+    #[cfg_attr(feature = "maybe-stdin", doc = "```rust")]
+    #[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+    /// use clap_stdin::{MaybeStdin, StdinOpts, Trim};
+    ///
+    /// # let _session = clap_stdin::StdinSession::with_input("first\r\nsecond\r\n");
+    /// let parse = MaybeStdin::<String>::with_opts(
+    ///     StdinOpts::new().normalize_newlines().trim(Trim::TrailingNewline),
+    /// );
+    /// assert_eq!(*parse("-").unwrap(), "first\nsecond");
+    /// ```
+    pub fn normalize_newlines(mut self) -> Self {
+        self.normalize_newlines = true;
+        self
+    }
+
+    /// Keep a leading UTF-8 byte order mark (`U+FEFF`) in text read from `stdin` or a file,
+    /// instead of removing it before the value is parsed
+    ///
+    /// Notepad and other Windows tools start files with a byte order mark, which most `FromStr`
+    /// impls reject, so it is removed from text: parsed values, lines and documents. Raw bytes are
+    /// never changed, E.g. from [`FileOrStdin::contents_bytes`](crate::FileOrStdin::contents_bytes),
+    /// its `Read` impl or a memory map.
+    pub fn keep_bom(mut self) -> Self {
+        self.keep_bom = true;
+        self
+    }
+
+    /// Read `stdin` interactively when it is a terminal, instead of failing with
+    /// [`StdinError::StdinIsTty`]
+    ///
+    /// The input is then typed by the user and ended with Ctrl-D (Ctrl-Z on Windows).
+    pub fn allow_terminal(mut self) -> Self {
+        self.allow_terminal = true;
+        self
+    }
+
+    /// Read `stdin` interactively when it is a terminal, showing `prompt` on `stderr` first
+    ///
+    /// For tools used both in pipelines and interactively; piped input is read without a prompt.
+    #[cfg_attr(feature = "maybe-stdin", doc = "```rust")]
+    #[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+    /// use clap::Parser;
+    /// use clap_stdin::{MaybeStdin, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = MaybeStdin::<String>::with_opts(
+    ///         StdinOpts::new().prompt("Enter value, end with Ctrl-D:")
+    ///     ))]
+    ///     message: MaybeStdin<String>,
+    /// }
+    /// ```
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    /// End input typed at a terminal with a line consisting only of `terminator` (E.g. `"."`),
+    /// instead of Ctrl-D, which is handy to paste documents
+    ///
+    /// A short hint about how to end the input is shown on `stderr` after the prompt. Like
+    /// [`Stdin::read_multiline`](crate::Stdin::read_multiline), the terminator line isn't part of
+    /// the value, and piped input is read entirely. Applies with [`StdinOpts::prompt`] or
+    /// [`StdinOpts::allow_terminal`].
+    pub fn terminator(mut self, terminator: impl Into<String>) -> Self {
+        self.terminator = Some(terminator.into());
+        self
+    }
+
+    /// Fail with [`StdinError::Timeout`] if `stdin` provides no data (and isn't closed) within
+    /// `timeout`, instead of waiting forever on a missing pipe
+    ///
+    /// Only the arrival of the first data is timed, not reading the entire input. After a timeout,
+    /// `stdin` can't be read anymore, so the error should end the program.
+    /// Can be overridden with the [`TIMEOUT_ENV`](crate::TIMEOUT_ENV) environment variable.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Call `callback` while the input is read, with the number of bytes read so far and the
+    /// total size of the input when it's known (the size of a file, or `None` for `stdin`)
+    ///
+    /// For driving a progress bar from large inputs. The bytes are counted as read from the file
+    /// or `stdin` (by the blocking or async readers), before any decompression or transcoding. A
+    /// memory map reports its whole size at once. Values aren't reported while the args
+    /// are parsed unless they're read at that point, like [`MaybeStdin`](crate::MaybeStdin) values.
+    #[cfg_attr(feature = "file", doc = "```rust")]
+    #[cfg_attr(not(feature = "file"), doc = "```ignore")]
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use std::sync::Arc;
+    /// use clap_stdin::{FileOrStdin, StdinOpts};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp = tempfile::NamedTempFile::new()?;
+    /// # std::fs::write(&tmp, "large input")?;
+    /// let progress = Arc::new(AtomicU64::new(0));
+    /// let opts = StdinOpts::new().on_progress({
+    ///     let progress = progress.clone();
+    ///     move |read, total| {
+    ///         assert_eq!(total, Some(11));
+    ///         progress.store(read, Ordering::Relaxed);
+    ///     }
+    /// });
+    /// let input = FileOrStdin::<String>::with_opts(opts)(tmp.path().to_str().unwrap())?;
+    /// input.contents()?;
+    /// assert_eq!(progress.load(Ordering::Relaxed), 11);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(u64, Option<u64>) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+
+    /// Check the contents of the value with `validator` while the arg is parsed, failing with
+    /// [`StdinError::Invalid`] and the returned message
+    ///
+    /// The raw contents are checked, before trimming and parsing, whether they come from `stdin`
+    /// or the arg value, so constraints like "not empty" or "at most 10k lines" are reported as
+    /// errors naming the arg. For [`FileOrStdin`](crate::FileOrStdin), the entire input is read
+    /// into memory while the arg is parsed to check it, and decoded with the [`StdinOpts::utf8`]
+    /// policy (so contents that aren't valid UTF-8 fail to parse, unless the policy is lossy).
+    #[cfg_attr(feature = "file", doc = "```rust")]
+    #[cfg_attr(not(feature = "file"), doc = "```ignore")]
+    /// use clap::Parser;
+    /// use clap_stdin::{FileOrStdin, StdinOpts};
+    ///
+    /// #[derive(Debug, Parser)]
+    /// struct Args {
+    ///     #[arg(value_parser = FileOrStdin::<String>::with_opts(StdinOpts::new().validate(|s| {
+    ///         match s.lines().count() {
+    ///             0..=10_000 => Ok(()),
+    ///             n => Err(format!("expected at most 10000 lines, got {n}")),
+    ///         }
+    ///     })))]
+    ///     input: FileOrStdin,
+    /// }
+    /// ```
+    pub fn validate(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(Validator(Arc::new(validator)));
+        self
+    }
+
+    /// Check `contents` with the configured validator, if any
+    pub(crate) fn check(&self, contents: &str) -> Result<(), StdinError> {
+        match &self.validator {
+            Some(Validator(validator)) => validator(contents).map_err(StdinError::Invalid),
+            None => Ok(()),
+        }
+    }
+
+    /// Prepare to read `stdin` for `consumer`: claim it, then if it is a terminal, show the
+    /// configured prompt, or fail with [`StdinError::StdinIsTty`] unless
+    /// [`StdinOpts::allow_terminal`] is set. Then wait for data for at most the configured timeout.
+    ///
+    /// With a [`StdinOpts::terminator`], the input typed at a terminal is read here, and returned
+    /// to be read instead of `stdin`.
+    pub(crate) fn prepare_stdin(&self, consumer: &str) -> Result<Option<Vec<u8>>, StdinError> {
+        claim_stdin(consumer)?;
+        if stdin_is_terminal() {
+            match (&self.prompt, self.allow_terminal) {
+                (Some(prompt), _) => eprintln!("{prompt}"),
+                (None, true) => {}
+                (None, false) => return Err(StdinError::StdinIsTty),
+            }
+            if let Some(terminator) = &self.terminator {
+                return Ok(Some(read_stdin_multiline(terminator)?.into_bytes()));
+            }
+        }
+        if let Some(timeout) = env_override(TIMEOUT_ENV)?
+            .map(Duration::from_secs)
+            .or(self.timeout)
+        {
+            wait_for_stdin(timeout)?;
+        }
+        Ok(None)
+    }
+
+    /// Wrap a reader over a file or `stdin` in the configured progress callback, if any
+    pub(crate) fn progress_reader(&self, reader: BoxedReader, total: Option<u64>) -> BoxedReader {
+        match &self.progress {
+            Some(callback) => callback.wrap(reader, total),
+            None => reader,
+        }
+    }
+
+    /// Wrap a reader over text input in the configured transcoding, if any
+    pub(crate) fn text_reader(&self, reader: BoxedReader) -> BoxedReader {
+        #[cfg(feature = "encoding")]
+        if self.detect_encoding || self.encoding.is_some() {
+            return super::transcode::transcoded(reader, self.encoding);
+        }
+        reader
+    }
+
+    /// Is the input read byte for byte, without decompression, transcoding, digests or a rate
+    /// limit that need every byte to go through a reader
+    #[cfg(feature = "mmap")]
+    pub(crate) fn reads_verbatim(&self) -> bool {
+        #[allow(unused_mut)]
+        let mut verbatim = self.rate_limit.is_none();
+        #[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+        {
+            verbatim &= !self.decompress;
+        }
+        #[cfg(feature = "encoding")]
+        {
+            verbatim &= !self.detect_encoding && self.encoding.is_none();
+        }
+        #[cfg(any(feature = "sha2", feature = "blake3"))]
+        {
+            verbatim &= self.digest.is_none();
+        }
+        verbatim
+    }
+
+    /// Remove a leading byte order mark (unless [`StdinOpts::keep_bom`] is set) and apply
+    /// [`StdinOpts::normalize_newlines`] to text read from `stdin` or a file
+    pub(crate) fn normalize(&self, value: String) -> String {
+        let value = match self.keep_bom {
+            true => value,
+            false => strip_bom(value),
+        };
+        match self.normalize_newlines && value.contains('\r') {
+            true => value.replace("\r\n", "\n"),
+            false => value,
+        }
+    }
+
+    /// Wrap a reader over text from a file or `stdin` in the configured transcoding, and in the
+    /// removal of a leading byte order mark
+    #[cfg(feature = "vec")]
+    pub(crate) fn input_reader(&self, reader: BoxedReader) -> BoxedReader {
+        Box::new(SkipBom::unless_kept(
+            self.text_reader(reader),
+            self.keep_bom,
+        ))
+    }
+
+    /// The configured UTF-8 policy, or the crate default
+    pub(crate) fn utf8_policy(&self) -> Utf8Policy {
+        self.utf8.unwrap_or_else(Utf8Policy::current_default)
+    }
+
+    /// Apply the overrides set with [`MAX_BYTES_ENV`] and [`RATE_LIMIT_ENV`]
+    #[cfg(feature = "file")]
+    pub(crate) fn with_env_overrides(mut self) -> Result<Self, StdinError> {
+        self.max_bytes = max_bytes_limit(Some(&self))?;
+        if let Some(rate_limit) = env_override(RATE_LIMIT_ENV)? {
+            self = self.rate_limit(rate_limit);
+        }
+        Ok(self)
+    }
+
+    /// Resolve the source of an arg value
+    ///
+    /// The sentinel escaped with a backslash (`\-` by default) is the literal sentinel value
+    #[cfg(any(
+        feature = "maybe-stdin",
+        feature = "from-source",
+        feature = "vec",
+        feature = "file",
+        feature = "output"
+    ))]
+    pub(crate) fn source(&self, s: &str) -> Source {
+        match &self.sentinel {
+            Some(sentinel) if s == sentinel && self.share_stdin => {
+                Source::SharedStdin(SharedStdin::new())
+            }
+            Some(sentinel) if s == sentinel => Source::stdin(),
+            Some(sentinel) if s.strip_prefix('\\') == Some(sentinel) => Source::literal(sentinel),
+            _ => Source::from_arg(s),
+        }
+    }
+
+    /// Resolve the source of an arg value like [`StdinOpts::source`], reading the value of an
+    /// environment variable named with the [`StdinOpts::env_prefix`]
+    #[cfg(any(feature = "maybe-stdin", feature = "from-source", feature = "vec"))]
+    pub(crate) fn resolve(&self, s: &str) -> Result<(Source, SourceKind), StdinError> {
+        let env_name = self
+            .env_prefix
+            .as_deref()
+            .and_then(|prefix| s.strip_prefix(prefix));
+        if let Some(name) = env_name {
+            let value = match std::env::var(name) {
+                Ok(value) => value,
+                Err(std::env::VarError::NotPresent) => {
+                    return Err(StdinError::EnvNotSet(name.to_owned()))
+                }
+                Err(std::env::VarError::NotUnicode(_)) => {
+                    return Err(StdinError::EnvNotUnicode(name.to_owned()))
+                }
+            };
+            return Ok((Source::Arg(value), SourceKind::Env(name.to_owned())));
+        }
+        let source = self.source(s);
+        let kind = source.source_kind();
+        Ok((source, kind))
+    }
+
+    /// Split an arg value on the configured delimiters, or on `default`
+    #[cfg(feature = "vec")]
+    pub(crate) fn split<'a>(
+        &'a self,
+        value: &'a str,
+        default: char,
+    ) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+        match (self.delimiters.as_slice(), self.split_whitespace) {
+            ([], false) => Box::new(value.split(default)),
+            (&[delimiter], false) => Box::new(value.split(delimiter)),
+            (delimiters, split_whitespace) => Box::new(
+                value
+                    .split(move |c: char| {
+                        delimiters.contains(&c) || (split_whitespace && c.is_whitespace())
+                    })
+                    .filter(|item| !item.is_empty()),
+            ),
+        }
+    }
+
+    /// The delimiter used to display split values: the first configured delimiter, or `default`
+    #[cfg(feature = "vec")]
+    pub(crate) fn display_delimiter(&self, default: char) -> char {
+        match (self.delimiters.first(), self.split_whitespace) {
+            (Some(&delimiter), _) => delimiter,
+            (None, true) => ' ',
+            (None, false) => default,
+        }
+    }
+
+    /// The configured trimming policy, or `default` for the type being parsed
+    #[cfg(any(feature = "maybe-stdin", feature = "vec", feature = "file"))]
+    pub(crate) fn trim_or(&self, default: Trim) -> Trim {
+        self.trim.unwrap_or(default)
+    }
+}
+
+/// A validator function, shared between the clones of the options
+type ValidateFn = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Validator registered with [`StdinOpts::validate`]
+#[derive(Clone)]
+pub(crate) struct Validator(ValidateFn);
+
+impl std::fmt::Debug for Validator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Validator")
+    }
+}
+
+/// Options are equal when they share the same registered validator
+impl PartialEq for Validator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Validator {}
+
+/// Numeric override from the environment variable `name`, if it's set (and not blank)
+///
+/// Invalid values are reported rather than ignored, since the overrides are meant to harden
+/// deployed binaries.
+pub(crate) fn env_override(name: &str) -> Result<Option<u64>, StdinError> {
+    match std::env::var(name) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => match value.trim().parse() {
+            Ok(number) => Ok(Some(number)),
+            Err(_) => Err(StdinError::EnvNotNumber(name.to_owned(), value)),
+        },
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(StdinError::EnvNotUnicode(name.to_owned())),
+    }
+}
+
+/// The limit on the size of the input read from `stdin`, a file or a descriptor: [`MAX_BYTES_ENV`]
+/// if it's set, or else the [`StdinOpts::max_bytes`] of `opts`
+pub(crate) fn max_bytes_limit(opts: Option<&StdinOpts>) -> Result<Option<u64>, StdinError> {
+    Ok(env_override(MAX_BYTES_ENV)?.or(opts.and_then(|opts| opts.max_bytes)))
+}
+
+/// Set an environment variable for a test
+///
+/// Tests run in parallel, so each test owns the variables it sets: their names are unique to the
+/// test (and prefixed with `CLAP_STDIN_TEST_`), they are set before being read and never removed.
+/// No other test reads them, so the order of tests doesn't matter. `std::env` serializes its own
+/// reads and writes of the environment, and the crate only reads it through `std::env`.
+#[cfg(test)]
+pub(crate) fn set_test_env(name: &str, value: impl AsRef<std::ffi::OsStr>) {
+    assert!(
+        name.starts_with("CLAP_STDIN_TEST_"),
+        "{name} isn't a test variable"
+    );
+    std::env::set_var(name, value);
+}
+
+impl Default for StdinOpts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_prepare_stdin() {
+    // Session input is never a terminal, so it is read without a prompt
+    for opts in [
+        StdinOpts::new(),
+        StdinOpts::new().allow_terminal(),
+        StdinOpts::new().prompt("Enter value:").terminator("."),
+    ] {
+        let _session = crate::StdinSession::with_input("piped");
+        assert_eq!(opts.prepare_stdin("test").unwrap(), None);
+        // stdin is claimed before any prompt is shown
+        assert!(matches!(
+            opts.prepare_stdin("test"),
+            Err(StdinError::StdInRepeatedUse)
+        ));
+    }
+}
+
+#[cfg(feature = "maybe-stdin")]
+#[test]
+fn test_sentinel() {
+    let opts = StdinOpts::new();
+    assert!(opts.source("-").is_stdin());
+    assert_eq!(opts.source("\\-").as_arg_str(), Some("-"));
+    assert_eq!(opts.source("\\x").as_arg_str(), Some("\\x"));
+    let opts = StdinOpts::new().sentinel("@-");
+    assert!(opts.source("@-").is_stdin());
+    assert_eq!(opts.source("\\@-").as_arg_str(), Some("@-"));
+    assert!(!opts.source("-").is_stdin());
+    let opts = StdinOpts::new().no_sentinel();
+    assert!(!opts.source("-").is_stdin());
+}
+
+#[cfg(feature = "maybe-stdin")]
+#[test]
+fn test_env_prefix() {
+    set_test_env("CLAP_STDIN_TEST_ENV_PREFIX", "value");
+    let opts = StdinOpts::new().env_prefix("env:");
+    let (source, kind) = opts.resolve("env:CLAP_STDIN_TEST_ENV_PREFIX").unwrap();
+    assert_eq!(source.as_arg_str(), Some("value"));
+    assert_eq!(kind, SourceKind::Env("CLAP_STDIN_TEST_ENV_PREFIX".into()));
+    assert!(matches!(
+        opts.resolve("env:CLAP_STDIN_TEST_ENV_PREFIX_UNSET"),
+        Err(StdinError::EnvNotSet(name)) if name == "CLAP_STDIN_TEST_ENV_PREFIX_UNSET"
+    ));
+    let (source, _) = StdinOpts::new()
+        .resolve("env:CLAP_STDIN_TEST_ENV_PREFIX")
+        .unwrap();
+    assert_eq!(source.as_arg_str(), Some("env:CLAP_STDIN_TEST_ENV_PREFIX"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let value = std::ffi::OsStr::from_bytes(b"caf\xe9");
+        set_test_env("CLAP_STDIN_TEST_ENV_PREFIX_NOT_UNICODE", value);
+        assert!(matches!(
+            opts.resolve("env:CLAP_STDIN_TEST_ENV_PREFIX_NOT_UNICODE"),
+            Err(StdinError::EnvNotUnicode(name)) if name == "CLAP_STDIN_TEST_ENV_PREFIX_NOT_UNICODE"
+        ));
+    }
+}
+
+#[cfg(feature = "vec")]
+#[test]
+fn test_split() {
+    let split =
+        |opts: StdinOpts, value| opts.split(value, ',').map(String::from).collect::<Vec<_>>();
+    assert_eq!(split(StdinOpts::new(), "a,,b"), ["a", "", "b"]);
+    assert_eq!(
+        split(StdinOpts::new().delimiter(';'), "a;b,c"),
+        ["a", "b,c"]
+    );
+    assert_eq!(
+        split(StdinOpts::new().delimiters([',', ';']), "a,;b;c"),
+        ["a", "b", "c"]
+    );
+    assert_eq!(
+        split(StdinOpts::new().split_whitespace(), "a  b\tc,d"),
+        ["a", "b", "c,d"]
+    );
+    assert_eq!(
+        StdinOpts::new().split_whitespace().display_delimiter(','),
+        ' '
+    );
+}
+
+#[test]
+fn test_trim() {
+    assert_eq!(Trim::None.apply(" a \n"), " a \n");
+    assert_eq!(Trim::TrailingNewline.apply(" a \r\n"), " a ");
+    assert_eq!(Trim::TrailingNewline.apply(" a \n\n"), " a \n");
+    assert_eq!(Trim::TrailingNewline.apply(" a "), " a ");
+    assert_eq!(Trim::End.apply(" a \n"), " a");
+    assert_eq!(Trim::Whitespace.apply(" a \n"), "a");
+}
+
+#[test]
+fn test_normalize_newlines() {
+    assert_eq!(StdinOpts::new().normalize("a\r\nb".into()), "a\r\nb");
+    let opts = StdinOpts::new().normalize_newlines();
+    assert_eq!(opts.normalize("\u{feff}a\r\nb\r\n".into()), "a\nb\n");
+    let opts = StdinOpts::new().keep_bom();
+    assert_eq!(opts.normalize("\u{feff}a".into()), "\u{feff}a");
+    assert_eq!(opts.normalize("a\rb".into()), "a\rb");
+}
+
+#[test]
+fn test_utf8_policy() {
+    let invalid = b"caf\xe9".to_vec();
+    let e = Utf8Policy::Strict.decode(invalid.clone()).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(Utf8Policy::Lossy.decode(invalid).unwrap(), "caf\u{FFFD}");
+    assert_eq!(Utf8Policy::Strict.decode(b"ok".to_vec()).unwrap(), "ok");
+    let opts = StdinOpts::new().utf8(Utf8Policy::Lossy);
+    assert_eq!(opts.utf8_policy(), Utf8Policy::Lossy);
+}
+
+#[test]
+fn test_env_override() {
+    assert!(matches!(
+        env_override("CLAP_STDIN_TEST_ENV_OVERRIDE_UNSET"),
+        Ok(None)
+    ));
+    set_test_env("CLAP_STDIN_TEST_ENV_OVERRIDE", " 42\n");
+    assert!(matches!(
+        env_override("CLAP_STDIN_TEST_ENV_OVERRIDE"),
+        Ok(Some(42))
+    ));
+    set_test_env("CLAP_STDIN_TEST_ENV_OVERRIDE_BLANK", "");
+    assert!(matches!(
+        env_override("CLAP_STDIN_TEST_ENV_OVERRIDE_BLANK"),
+        Ok(None)
+    ));
+    set_test_env("CLAP_STDIN_TEST_ENV_OVERRIDE_INVALID", "10MB");
+    assert!(matches!(
+        env_override("CLAP_STDIN_TEST_ENV_OVERRIDE_INVALID"),
+        Err(StdinError::EnvNotNumber(_, value)) if value == "10MB"
+    ));
+}
@@ -0,0 +1,201 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::opts::max_bytes_limit;
+use super::{Source, SourceKind, StdinError, StdinOpts};
+
+/// Wrapper struct for args that must be a path on disk, with `stdin` materialized to a temp file
+///
+/// Useful when wrapping external tools that only accept file paths: a user-supplied path is used
+/// as is, while the contents of `stdin` (for `-`) are written to a temp file when the arg is parsed.
+/// The temp file is removed when the last clone of the `PathOrStdin` is dropped.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::PathOrStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: PathOrStdin,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// std::process::Command::new("wc")
+///     .arg(args.input.path())
+///     .status()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ cat input.txt | ./example -
+/// $ ./example input.txt
+/// ```
+#[derive(Clone)]
+pub struct PathOrStdin {
+    path: PathBuf,
+    temp: Option<Arc<TempPath>>,
+}
+
+impl PathOrStdin {
+    /// The path of the user-supplied file, or of the temp file holding the contents of stdin
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where the contents of the path came from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        match self.temp {
+            Some(_) => SourceKind::Stdin,
+            None => SourceKind::File(self.path.clone()),
+        }
+    }
+
+    /// Was the content read from stdin (and written to a temp file)
+    pub fn is_stdin(&self) -> bool {
+        self.temp.is_some()
+    }
+}
+
+impl FromStr for PathOrStdin {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+impl PathOrStdin {
+    /// A `value_parser` that materializes `stdin` as configured by `opts`
+    ///
+    /// Besides the sentinel, [`StdinOpts::max_bytes`] limits the size of the temp file, and
+    /// [`StdinOpts::timeout`] and [`StdinOpts::prompt`] apply when `stdin` is read. A path given
+    /// as the arg value is used as is, without reading (or checking the size of) the file.
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        match opts.source(s) {
+            source @ (Source::Stdin(_) | Source::SharedStdin(_) | Source::Fd(_)) => {
+                let kind = source.source_kind();
+                let reader = source.reader_with(std::any::type_name::<Self>(), Some(opts))?;
+                let max_bytes = max_bytes_limit(Some(opts))?;
+                let temp = TempPath::from_reader(reader, max_bytes)?;
+                debug_log!("PathOrStdin: materialized {} to {:?}", kind.name(), temp.0);
+                Ok(Self {
+                    path: temp.0.clone(),
+                    temp: Some(Arc::new(temp)),
+                })
+            }
+            Source::Arg(path) => Ok(Self {
+                path: path.into(),
+                temp: None,
+            }),
+            Source::Path(path) => Ok(Self { path, temp: None }),
+        }
+    }
+}
+
+impl std::fmt::Debug for PathOrStdin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PathOrStdin")
+            .field("source", &self.source_kind())
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl AsRef<Path> for PathOrStdin {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl std::ops::Deref for PathOrStdin {
+    type Target = Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+/// A temp file that is removed on drop
+struct TempPath(PathBuf);
+
+impl TempPath {
+    /// Create a new temp file holding the contents of `reader`, only accessible by the current
+    /// user on Unix (the shared temp dir may be readable by others)
+    fn from_reader(reader: impl io::Read, max_bytes: Option<u64>) -> Result<Self, StdinError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let (temp, mut file) = loop {
+            let name = format!(
+                "clap-stdin-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = std::env::temp_dir().join(name);
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            match options.open(&path) {
+                Ok(file) => break (TempPath(path), file),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        };
+        let limit = max_bytes.map_or(u64::MAX, |max_bytes| max_bytes.saturating_add(1));
+        match io::copy(&mut io::Read::take(reader, limit), &mut file)? {
+            0 => return Err(StdinError::StdinClosed),
+            len if max_bytes.is_some_and(|max_bytes| len > max_bytes) => {
+                return Err(StdinError::InputTooLarge(len - 1));
+            }
+            _ => {}
+        }
+        File::sync_all(&file)?;
+        Ok(temp)
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn test_temp_path() {
+    let temp = TempPath::from_reader(io::Cursor::new("contents"), None).unwrap();
+    let path = temp.0.clone();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "contents");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    drop(temp);
+    assert!(!path.exists());
+
+    assert!(matches!(
+        TempPath::from_reader(io::empty(), None),
+        Err(StdinError::StdinClosed)
+    ));
+    assert!(matches!(
+        TempPath::from_reader(io::Cursor::new("contents"), Some(4)),
+        Err(StdinError::InputTooLarge(4))
+    ));
+    assert!(TempPath::from_reader(io::Cursor::new("contents"), Some(8)).is_ok());
+
+    let input: PathOrStdin = "/path/to/file".parse().unwrap();
+    assert!(!input.is_stdin());
+    assert_eq!(input.path(), Path::new("/path/to/file"));
+}
@@ -0,0 +1,185 @@
+use std::ffi::OsString;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::{os_string_from_bytes, FileOrStdin, SkipBom, SourceKind, StdinError, StdinOpts};
+
+/// Wrapper struct to read a list of paths, one per line, from a list file or from `stdin`
+///
+/// Like [`FileOrStdinVec<PathBuf>`](crate::FileOrStdinVec), with a leading `~` of each path
+/// expanded to the home directory (from `HOME`, or `USERPROFILE` on Windows). `~user` paths are
+/// kept as is. Each line is a path as is, without trimming and even if it isn't valid UTF-8 (on
+/// Unix), while blank lines are skipped. With the [`PathsFromStdin::must_exist`] parser, every
+/// path is checked when the arg is parsed, and the missing ones are all reported at once.
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::{PathsFromStdin, StdinOpts};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(value_parser = PathsFromStdin::must_exist(StdinOpts::new()))]
+///     files: PathsFromStdin,
+/// }
+///
+/// let args = Args::parse();
+/// for file in args.files.iter() {
+///     println!("file={}", file.display());
+/// }
+/// ```
+///
+/// ```sh
+/// $ find . -name '*.txt' | ./example -
+/// $ ./example files.txt
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathsFromStdin {
+    inner: Vec<PathBuf>,
+    source: SourceKind,
+}
+
+impl PathsFromStdin {
+    fn parse_with_opts(s: &str, opts: &StdinOpts, must_exist: bool) -> Result<Self, StdinError> {
+        let input = FileOrStdin::<String>::with_opts(opts.clone())(s)?;
+        let source = input.source_kind();
+        let skip = opts.line_filter();
+        let mut inner = Vec::new();
+        for line in SkipBom::unless_kept(input, opts.keep_bom).split(b'\n') {
+            let mut line = line.map_err(StdinError::from_io)?;
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if line.is_empty() || std::str::from_utf8(&line).is_ok_and(&skip) {
+                continue;
+            }
+            inner.push(expand_home(os_string_from_bytes(line)?));
+        }
+        if must_exist {
+            let missing: Vec<PathBuf> = inner
+                .iter()
+                .filter(|path| !path.exists())
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                return Err(StdinError::PathsNotFound(missing));
+            }
+        }
+        Ok(Self { inner, source })
+    }
+
+    /// A `value_parser` that reads the paths as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts, false)
+    }
+
+    /// A `value_parser` that also checks that every path exists when the arg is parsed, failing
+    /// with [`StdinError::PathsNotFound`] listing all the missing paths
+    pub fn must_exist(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts, true)
+    }
+
+    /// Where these paths were read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these paths read from stdin (shorthand for checking [`PathsFromStdin::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the paths from the wrapper
+    pub fn into_inner(self) -> Vec<PathBuf> {
+        self.inner
+    }
+}
+
+/// Replace a leading `~` component of `path` with the home directory, if it's known
+fn expand_home(path: OsString) -> PathBuf {
+    let home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" })
+        .filter(|home| !home.is_empty());
+    let path = PathBuf::from(path);
+    match (home, path.strip_prefix("~")) {
+        (Some(home), Ok(rest)) if rest.as_os_str().is_empty() => PathBuf::from(home),
+        (Some(home), Ok(rest)) => PathBuf::from(home).join(rest),
+        _ => path,
+    }
+}
+
+impl FromStr for PathsFromStdin {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default(), false)
+    }
+}
+
+impl std::ops::Deref for PathsFromStdin {
+    type Target = Vec<PathBuf>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl std::ops::DerefMut for PathsFromStdin {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl IntoIterator for PathsFromStdin {
+    type Item = PathBuf;
+    type IntoIter = std::vec::IntoIter<PathBuf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+#[test]
+fn test_paths_from_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let existing = dir.path().join("existing.txt");
+    std::fs::write(&existing, "").unwrap();
+    let input = format!("{}\n\n~\nmissing-1\nmissing-2\n", existing.display());
+
+    let _session = crate::StdinSession::with_input(input.clone());
+    let paths: PathsFromStdin = "-".parse().unwrap();
+    assert!(paths.is_stdin());
+    assert_eq!(paths.len(), 4);
+    assert_eq!(paths[0], existing);
+    if let Some(home) = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }) {
+        assert_eq!(paths[1], std::path::Path::new(&home));
+    }
+
+    let _session = crate::StdinSession::with_input(input);
+    let parse = PathsFromStdin::must_exist(StdinOpts::new());
+    let error = parse("-").unwrap_err();
+    assert!(
+        matches!(&error, StdinError::PathsNotFound(missing) if missing.len() == 2),
+        "{error}"
+    );
+    assert_eq!(error.to_string(), "not found: 'missing-1', 'missing-2'");
+
+    // Paths are kept exactly, without trimming
+    let _session = crate::StdinSession::with_input(" spaced \r\n");
+    let paths: PathsFromStdin = "-".parse().unwrap();
+    assert_eq!(paths[0], std::path::Path::new(" spaced "));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_paths_from_stdin_not_utf8() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let _session = crate::StdinSession::with_input(b"caf\xe9.txt\n".to_vec());
+    let paths: PathsFromStdin = "-".parse().unwrap();
+    assert_eq!(paths[0].as_os_str().as_bytes(), b"caf\xe9.txt");
+}
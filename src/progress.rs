@@ -0,0 +1,152 @@
+//! Progress reporting while reading, see [`StdinOpts::on_progress`](crate::StdinOpts::on_progress)
+use std::io::{self, BufRead, Read};
+#[cfg(any(feature = "async", feature = "tokio"))]
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(any(feature = "async", feature = "tokio"))]
+use std::task::{Context, Poll};
+
+use super::BoxedReader;
+
+/// Callback registered with [`StdinOpts::on_progress`](crate::StdinOpts::on_progress)
+#[derive(Clone)]
+pub(crate) struct ProgressCallback(Arc<dyn Fn(u64, Option<u64>) + Send + Sync>);
+
+impl ProgressCallback {
+    pub(crate) fn new(callback: impl Fn(u64, Option<u64>) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Report `read` bytes read so far (out of `total`, when known)
+    pub(crate) fn report(&self, read: u64, total: Option<u64>) {
+        (self.0)(read, total)
+    }
+
+    /// Wrap `reader`, reporting the bytes read from it (out of `total`, when known)
+    pub(crate) fn wrap(&self, reader: BoxedReader, total: Option<u64>) -> BoxedReader {
+        Box::new(self.reader(reader, total))
+    }
+
+    /// Wrap a reader of any kind, E.g. an async one
+    pub(crate) fn reader<R>(&self, inner: R, total: Option<u64>) -> ProgressReader<R> {
+        ProgressReader {
+            inner,
+            read: 0,
+            total,
+            callback: self.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Options are equal when they share the same registered callback
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+/// Reader calling the progress callback after each read
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    read: u64,
+    total: Option<u64>,
+    callback: ProgressCallback,
+}
+
+impl<R> ProgressReader<R> {
+    fn advance(&mut self, n: usize) {
+        if n > 0 {
+            self.read += n as u64;
+            self.callback.report(self.read, self.total);
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.advance(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for ProgressReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.advance(amt);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: futures_lite::AsyncRead + Unpin> futures_lite::AsyncRead for ProgressReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = std::task::ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.advance(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for ProgressReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        std::task::ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.advance(buf.filled().len() - filled);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn test_progress_reader() {
+    use std::sync::Mutex;
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let callback = ProgressCallback::new({
+        let reports = reports.clone();
+        move |read, total| reports.lock().unwrap().push((read, total))
+    });
+    let mut reader = callback.wrap(Box::new(io::Cursor::new("first\nsecond\n")), Some(13));
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    reader.read_to_string(&mut line).unwrap();
+    assert_eq!(line, "first\nsecond\n");
+    assert_eq!(reports.lock().unwrap().last(), Some(&(13, Some(13))));
+    assert_eq!(reports.lock().unwrap()[0], (6, Some(13)));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_progress_reader_futures() {
+    use futures_lite::AsyncReadExt;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    let read = Arc::new(AtomicU64::new(0));
+    let callback = ProgressCallback::new({
+        let read = read.clone();
+        move |n, _| read.store(n, Ordering::Relaxed)
+    });
+    let mut reader = callback.reader(futures_lite::io::Cursor::new("contents"), None);
+    let mut contents = String::new();
+    futures_lite::future::block_on(reader.read_to_string(&mut contents)).unwrap();
+    assert_eq!(read.load(Ordering::Relaxed), 8);
+}
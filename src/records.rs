@@ -0,0 +1,79 @@
+use std::io::{self, BufRead};
+
+use super::Utf8Policy;
+
+/// Iterator over the records of a reader, split on a (multi-byte) record separator
+///
+/// Empty records are skipped, so leading and trailing separators (E.g. the `\x1e` that starts
+/// each record of an [RFC 7464](https://www.rfc-editor.org/rfc/rfc7464) JSON text sequence) don't
+/// produce empty items. Created by [`Stdin::records`](crate::Stdin::records).
+pub struct Records<R> {
+    reader: R,
+    separator: Vec<u8>,
+    utf8: Utf8Policy,
+}
+
+impl<R: BufRead> Records<R> {
+    pub(crate) fn new(reader: R, separator: impl Into<Vec<u8>>) -> Self {
+        Self {
+            reader,
+            separator: separator.into(),
+            utf8: Utf8Policy::current_default(),
+        }
+    }
+
+    /// Read up to and including the next separator (or the end of input)
+    fn read_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let Some(&last) = self.separator.last() else {
+            // An empty separator yields the entire input as a single record
+            let mut record = Vec::new();
+            self.reader.read_to_end(&mut record)?;
+            return Ok((!record.is_empty()).then_some(record));
+        };
+        let mut record = Vec::new();
+        loop {
+            if self.reader.read_until(last, &mut record)? == 0 {
+                return Ok((!record.is_empty()).then_some(record));
+            }
+            if record.ends_with(&self.separator) {
+                record.truncate(record.len() - self.separator.len());
+                return Ok(Some(record));
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.read_record() {
+                Ok(Some(record)) if record.is_empty() => continue,
+                Ok(Some(record)) => return Some(self.utf8.decode(record)),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_records() {
+    let records = |input: &str, separator: &str| {
+        Records::new(io::Cursor::new(input.to_owned()), separator)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap()
+    };
+    assert_eq!(
+        records("\x1e{\"a\":1}\n\x1e{\"b\":2}\n", "\x1e"),
+        ["{\"a\":1}\n", "{\"b\":2}\n"]
+    );
+    assert_eq!(
+        records("a: 1\n---\nb: 2\n---\n", "\n---\n"),
+        ["a: 1", "b: 2"]
+    );
+    assert_eq!(records("a--b-c--", "--"), ["a", "b-c"]);
+    assert_eq!(records("", "--"), Vec::<String>::new());
+    assert_eq!(records("abc", ""), ["abc"]);
+}
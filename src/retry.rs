@@ -0,0 +1,40 @@
+use std::io::{self, Read};
+
+/// Reader that retries reads interrupted by a signal (`EINTR`)
+///
+/// Harmless signals like `SIGWINCH` can interrupt a blocking read, which `BufReader` and
+/// single `read` calls surface as [`io::ErrorKind::Interrupted`] mid-parse.
+pub(crate) struct RetryInterrupted<R>(pub(crate) R);
+
+impl<R: Read> Read for RetryInterrupted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.0.read(buf) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                result => return result,
+            }
+        }
+    }
+}
+
+#[test]
+fn test_retry_interrupted() {
+    struct Flaky(usize);
+
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0 += 1;
+            match self.0 {
+                1 | 2 => Err(io::ErrorKind::Interrupted.into()),
+                3 => {
+                    buf[0] = b'x';
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    let mut reader = io::BufReader::new(RetryInterrupted(Flaky(0)));
+    assert_eq!(io::BufRead::fill_buf(&mut reader).unwrap(), b"x");
+}
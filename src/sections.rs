@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::StdinError;
+
+/// Named sections of a single input, delimited by header lines like `--- name ---`
+///
+/// Lets one pipe carry several logical inputs to different parts of a program. `Sections`
+/// implements `FromStr`, so it can be wrapped in [`MaybeStdin`](crate::MaybeStdin) or
+/// [`FileOrStdin`](crate::FileOrStdin):
+#[cfg_attr(feature = "file", doc = "```rust")]
+#[cfg_attr(not(feature = "file"), doc = "```ignore")]
+/// use clap::Parser;
+/// use clap_stdin::{FileOrStdin, Sections};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin<Sections>,
+/// }
+///
+/// let sections: Sections = "--- query ---\nSELECT 1;\n--- params ---\n{}\n".parse().unwrap();
+/// assert_eq!(sections.get("query"), Some("SELECT 1;\n"));
+/// assert_eq!(sections.get("params"), Some("{}\n"));
+/// ```
+///
+/// ```sh
+/// $ cat <<EOF | ./example -
+/// --- query ---
+/// SELECT 1;
+/// --- params ---
+/// {}
+/// EOF
+/// ```
+///
+/// Contents before the first header are kept under the empty name `""` (unless blank), and
+/// repeating a section name is an error.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sections {
+    sections: Vec<(String, String)>,
+}
+
+impl Sections {
+    /// The contents of the section `name`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, contents)| contents.as_str())
+    }
+
+    /// The section names, in the order they appeared in
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Iterate over the `(name, contents)` of the sections, in the order they appeared in
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sections
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.as_str()))
+    }
+
+    /// Number of sections
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    /// Are there no sections
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Take the sections as a map of name to contents
+    pub fn into_map(self) -> BTreeMap<String, String> {
+        self.sections.into_iter().collect()
+    }
+
+    fn push(&mut self, name: &str, contents: &str) -> Result<(), StdinError> {
+        if self.get(name).is_some() {
+            return Err(StdinError::FromStr(format!("duplicate section '{name}'")));
+        }
+        self.sections.push((name.to_owned(), contents.to_owned()));
+        Ok(())
+    }
+}
+
+/// The section name of a `--- name ---` header line
+fn header_name(line: &str) -> Option<&str> {
+    let name = line.trim().strip_prefix("---")?.strip_suffix("---")?.trim();
+    (!name.is_empty()).then_some(name)
+}
+
+impl FromStr for Sections {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sections = Sections::default();
+        let mut current: Option<&str> = None;
+        let mut start = 0;
+        let mut offset = 0;
+        for line in s.split_inclusive('\n') {
+            if let Some(name) = header_name(line) {
+                let contents = &s[start..offset];
+                match current {
+                    Some(current) => sections.push(current, contents)?,
+                    None if !contents.trim().is_empty() => sections.push("", contents)?,
+                    None => {}
+                }
+                current = Some(name);
+                start = offset + line.len();
+            }
+            offset += line.len();
+        }
+        match current {
+            Some(current) => sections.push(current, &s[start..])?,
+            None if !s.trim().is_empty() => sections.push("", s)?,
+            None => {}
+        }
+        Ok(sections)
+    }
+}
+
+impl<'a> IntoIterator for &'a Sections {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Box<dyn Iterator<Item = (&'a str, &'a str)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+#[test]
+fn test_sections() {
+    let sections: Sections = "preamble\n--- a ---\nx\ny\n---b---\r\n\n--- c ---"
+        .parse()
+        .unwrap();
+    assert_eq!(sections.names().collect::<Vec<_>>(), ["", "a", "b", "c"]);
+    assert_eq!(sections.get(""), Some("preamble\n"));
+    assert_eq!(sections.get("a"), Some("x\ny\n"));
+    assert_eq!(sections.get("b"), Some("\n"));
+    assert_eq!(sections.get("c"), Some(""));
+    assert_eq!(sections.get("d"), None);
+
+    let sections: Sections = "\n--- a ---\nx".parse().unwrap();
+    assert_eq!(
+        sections.into_map().into_iter().collect::<Vec<_>>(),
+        [("a".into(), "x".into())]
+    );
+
+    // A line of only dashes is not a header
+    let sections: Sections = "------\n".parse().unwrap();
+    assert_eq!(sections.get(""), Some("------\n"));
+
+    assert!(matches!(
+        "--- a ---\n--- a ---\n".parse::<Sections>(),
+        Err(StdinError::FromStr(_))
+    ));
+}
@@ -0,0 +1,337 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Cursor, IsTerminal, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use super::opts::max_bytes_limit;
+use super::{read_to_end_limited, BoxedReader, Records, RetryInterrupted, StdinError, StdinGuard};
+
+thread_local! {
+    /// Input of the innermost [`StdinSession`] on this thread, if it was given any
+    static SESSION_INPUT: RefCell<Option<Cursor<Vec<u8>>>> = const { RefCell::new(None) };
+
+    /// Input of the innermost [`StdinSession`] on this thread, once read while the cache is
+    /// enabled
+    static SESSION_CACHE: RefCell<Option<Arc<[u8]>>> = const { RefCell::new(None) };
+
+    /// Records of the input of the innermost [`StdinSession`] on this thread not handed out yet,
+    /// once read while `stdin` is split
+    static SESSION_SPLIT: RefCell<Option<VecDeque<String>>> = const { RefCell::new(None) };
+}
+
+static CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Contents of the process `stdin`, once read while the cache is enabled
+static STDIN_CACHE: Mutex<Option<Arc<[u8]>>> = Mutex::new(None);
+
+/// Keep the contents of `stdin` once read, so every later read returns them again
+pub(crate) fn enable_stdin_cache() {
+    CACHE_ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Separator splitting `stdin` between the args reading it
+static SPLIT_SEPARATOR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Records of the process `stdin` not handed out yet, once read while it is split
+static STDIN_SPLIT: Mutex<Option<VecDeque<String>>> = Mutex::new(None);
+
+/// Split `stdin` on `separator`, handing each read the next record
+pub(crate) fn split_stdin_records(separator: String) {
+    *SPLIT_SEPARATOR.lock().unwrap_or_else(|e| e.into_inner()) = Some(separator);
+}
+
+/// Stop splitting `stdin`, dropping the records not handed out yet
+pub(crate) fn reset_stdin_split() {
+    *SPLIT_SEPARATOR.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    *STDIN_SPLIT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    SESSION_SPLIT.with(|split| *split.borrow_mut() = None);
+}
+
+/// Is `stdin` split into records, so each read gets the next one
+pub(crate) fn stdin_is_split() -> bool {
+    SPLIT_SEPARATOR
+        .lock()
+        .is_ok_and(|separator| separator.is_some())
+}
+
+/// Reader over the next record of `stdin`, if it's split into records
+///
+/// The records of the input of a [`StdinSession`] are kept by the session, those of the process
+/// `stdin` for the whole process.
+fn split_stdin_reader() -> Option<BoxedReader> {
+    let separator = SPLIT_SEPARATOR
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()?;
+    let record = if SESSION_SPLIT.with(|split| split.borrow().is_some()) || has_session_input() {
+        SESSION_SPLIT.with(|split| next_record(&mut split.borrow_mut(), &separator))
+    } else {
+        next_record(
+            &mut STDIN_SPLIT.lock().unwrap_or_else(|e| e.into_inner()),
+            &separator,
+        )
+    };
+    Some(match record {
+        Ok(record) => Box::new(Cursor::new(record.into_bytes())),
+        Err(e) => Box::new(FailedRead(Some(e))),
+    })
+}
+
+/// The next of `records`, reading `stdin` (up to [`MAX_BYTES_ENV`](crate::MAX_BYTES_ENV)) and splitting it on
+/// `separator` first if it wasn't read yet
+fn next_record(records: &mut Option<VecDeque<String>>, separator: &str) -> io::Result<String> {
+    if records.is_none() {
+        let input = read_to_end_limited(uncached_stdin_reader(), max_bytes_limit(None)?)?;
+        let split = Records::new(Cursor::new(input), separator);
+        *records = Some(split.collect::<io::Result<_>>()?);
+    }
+    Ok(records
+        .as_mut()
+        .and_then(VecDeque::pop_front)
+        .unwrap_or_default())
+}
+
+/// Has `stdin` been read into the cache, so it can be read again
+pub(crate) fn stdin_is_cached() -> bool {
+    CACHE_ENABLED.load(Ordering::SeqCst)
+        && (SESSION_CACHE.with(|cache| cache.borrow().is_some())
+            || (!has_session_input() && STDIN_CACHE.lock().is_ok_and(|cache| cache.is_some())))
+}
+
+/// Reader over `stdin`: the input of the current [`StdinSession`], or the process `stdin`
+///
+/// The session input is taken, so callers must have claimed `stdin` first. While the cache is
+/// enabled, the first read loads the entire input (up to [`MAX_BYTES_ENV`](crate::MAX_BYTES_ENV)) into the cache of the
+/// session, or the process-wide cache for the process `stdin`, and every read returns it. While
+/// `stdin` is split into records, each read returns the next record instead.
+pub(crate) fn stdin_reader() -> BoxedReader {
+    if let Some(reader) = split_stdin_reader() {
+        return reader;
+    }
+    if !CACHE_ENABLED.load(Ordering::SeqCst) {
+        return uncached_stdin_reader();
+    }
+    if let Some(contents) = SESSION_CACHE.with(|cache| cache.borrow().clone()) {
+        return Box::new(Cursor::new(contents));
+    }
+    if let Some(input) = take_session_input() {
+        return match max_bytes_limit(None).and_then(|max| read_to_end_limited(input, max)) {
+            Ok(input) => {
+                let contents: Arc<[u8]> = input.into();
+                SESSION_CACHE.with(|cache| *cache.borrow_mut() = Some(contents.clone()));
+                Box::new(Cursor::new(contents))
+            }
+            Err(e) => Box::new(FailedRead(Some(e.into()))),
+        };
+    }
+    let mut cache = STDIN_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.is_none() {
+        match max_bytes_limit(None)
+            .and_then(|max| read_to_end_limited(uncached_stdin_reader(), max))
+        {
+            Ok(input) => *cache = Some(input.into()),
+            Err(e) => return Box::new(FailedRead(Some(e.into()))),
+        }
+    }
+    let contents = cache.clone().expect("stdin was cached");
+    Box::new(Cursor::new(contents))
+}
+
+fn uncached_stdin_reader() -> BoxedReader {
+    match take_session_input() {
+        Some(input) => Box::new(input),
+        None => Box::new(io::BufReader::new(RetryInterrupted(io::stdin()))),
+    }
+}
+
+/// Reader reporting the error of filling the cache on the first read
+struct FailedRead(Option<io::Error>);
+
+impl Read for FailedRead {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        self.0.take().map_or(Ok(0), Err)
+    }
+}
+
+impl BufRead for FailedRead {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.0.take().map_or(Ok(&[]), Err)
+    }
+
+    fn consume(&mut self, _amt: usize) {}
+}
+
+/// Does the current [`StdinSession`] hold input that wasn't read yet
+fn has_session_input() -> bool {
+    SESSION_INPUT.with(|input| input.borrow().is_some())
+}
+
+/// Take the input of the current [`StdinSession`], if it was given any
+pub(crate) fn take_session_input() -> Option<Cursor<Vec<u8>>> {
+    SESSION_INPUT.with(|input| input.borrow_mut().take())
+}
+
+/// Run `f` on `stdin` without taking it, so the rest of the input can be read afterwards
+pub(crate) fn with_stdin<R>(f: impl FnOnce(&mut dyn BufRead) -> R) -> R {
+    SESSION_INPUT.with(|input| match input.borrow_mut().as_mut() {
+        Some(input) => f(input),
+        None => f(&mut io::stdin().lock()),
+    })
+}
+
+/// Is `stdin` an interactive terminal (never the case for session input)
+pub(crate) fn stdin_is_terminal() -> bool {
+    !has_session_input() && io::stdin().is_terminal()
+}
+
+/// Wait until `stdin` has data (or is closed), failing with [`StdinError::Timeout`] after `timeout`
+///
+/// The process `stdin` is polled from a helper thread, since reads can't be cancelled: the data
+/// it waits for stays buffered in `stdin` for the actual read. After a timeout, the thread keeps
+/// `stdin` locked, so the [`StdinError::Timeout`] is always returned to the caller rather than
+/// falling back to another read of `stdin`.
+pub(crate) fn wait_for_stdin(timeout: Duration) -> Result<(), StdinError> {
+    if has_session_input() {
+        return Ok(());
+    }
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = io::stdin().lock().fill_buf();
+        let _ = sender.send(());
+    });
+    receiver
+        .recv_timeout(timeout)
+        .map_err(|_| StdinError::Timeout(timeout))
+}
+
+/// Handle over the `stdin` state of one parse of the command line
+///
+/// Shells, REPLs and daemons that parse many sub-invocations in one process can't rely on the
+/// process-wide "stdin may only be read once" rule. Create a session per invocation instead:
+/// while it is alive, `stdin` starts out unconsumed on the creating thread (like a [`StdinGuard`]),
+/// and args reading `-` read the session's input, if it was given any.
+///
+/// [`Stdin::lines`](crate::Stdin::lines), [`Stdin::records`](crate::Stdin::records) and
+/// [`Stdin::bytes`](crate::Stdin::bytes) read the session's input too.
+///
+#[cfg_attr(feature = "maybe-stdin", doc = "```rust")]
+#[cfg_attr(not(feature = "maybe-stdin"), doc = "```ignore")]
+/// use clap::Parser;
+/// use clap_stdin::{MaybeStdin, StdinSession};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     value: MaybeStdin<u32>,
+/// }
+///
+/// for input in ["1", "2"] {
+///     let session = StdinSession::with_input(input);
+///     let args = Args::parse_from(["example", "-"]);
+///     assert_eq!(*args.value, input.parse::<u32>().unwrap());
+///     assert!(session.is_consumed());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct StdinSession {
+    guard: StdinGuard,
+    previous: Option<Cursor<Vec<u8>>>,
+    previous_cache: Option<Arc<[u8]>>,
+    previous_split: Option<VecDeque<String>>,
+}
+
+impl StdinSession {
+    /// Start a session reading the process `stdin`
+    pub fn new() -> Self {
+        Self::start(None)
+    }
+
+    /// Start a session where `stdin` holds `input`
+    pub fn with_input(input: impl Into<Vec<u8>>) -> Self {
+        Self::start(Some(Cursor::new(input.into())))
+    }
+
+    fn start(input: Option<Cursor<Vec<u8>>>) -> Self {
+        let guard = StdinGuard::scoped();
+        let previous = SESSION_INPUT.with(|session| session.replace(input));
+        let previous_cache = SESSION_CACHE.with(|cache| cache.replace(None));
+        let previous_split = SESSION_SPLIT.with(|split| split.replace(None));
+        Self {
+            guard,
+            previous,
+            previous_cache,
+            previous_split,
+        }
+    }
+
+    /// Has `stdin` been consumed within this session
+    pub fn is_consumed(&self) -> bool {
+        self.guard.is_consumed()
+    }
+}
+
+impl Default for StdinSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for StdinSession {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SESSION_INPUT.with(|session| *session.borrow_mut() = previous);
+        let previous_cache = self.previous_cache.take();
+        SESSION_CACHE.with(|cache| *cache.borrow_mut() = previous_cache);
+        let previous_split = self.previous_split.take();
+        SESSION_SPLIT.with(|split| *split.borrow_mut() = previous_split);
+    }
+}
+
+#[cfg(feature = "maybe-stdin")]
+#[test]
+fn test_session_input() {
+    use super::{MaybeStdin, Stdin, StdinError};
+
+    let session = StdinSession::with_input("header\n42\n");
+    assert!(!stdin_is_terminal());
+    assert_eq!(Stdin.read_line_once().unwrap().as_deref(), Some("header"));
+    let value: MaybeStdin<u32> = "-".parse().unwrap();
+    assert_eq!(*value, 42);
+    assert!(session.is_consumed());
+    assert!(matches!(
+        "-".parse::<MaybeStdin<u32>>(),
+        Err(StdinError::StdInRepeatedUse)
+    ));
+    drop(session);
+
+    let _session = StdinSession::with_input("7");
+    let value: MaybeStdin<u32> = "-".parse().unwrap();
+    assert_eq!(*value, 7);
+}
+
+#[test]
+fn test_session_stdin_readers() {
+    use super::Stdin;
+
+    let _session = StdinSession::with_input("a\r\nb\n");
+    let lines = Stdin.lines().unwrap().collect::<io::Result<Vec<_>>>();
+    assert_eq!(lines.unwrap(), ["a", "b"]);
+
+    let _session = StdinSession::with_input("a--b--");
+    let records = Stdin.records("--").unwrap().collect::<io::Result<Vec<_>>>();
+    assert_eq!(records.unwrap(), ["a", "b"]);
+
+    let _session = StdinSession::with_input("ab");
+    let bytes = Stdin.bytes().unwrap().collect::<io::Result<Vec<_>>>();
+    assert_eq!(bytes.unwrap(), b"ab");
+
+    // Comments are skipped without `skip_blank`
+    let _session = StdinSession::with_input("\u{feff}# list\na\n\n  # b\nc");
+    let opts = crate::StdinOpts::new().comment_prefix("#");
+    let lines = Stdin
+        .lines_with(&opts)
+        .unwrap()
+        .collect::<io::Result<Vec<_>>>();
+    assert_eq!(lines.unwrap(), ["a", "", "c"]);
+}
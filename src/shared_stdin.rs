@@ -0,0 +1,165 @@
+//! `stdin` shared by the clones of a [`Source`](crate::Source), see [`SharedStdin`]
+use std::io::{self, Cursor};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use super::opts::max_bytes_limit;
+use super::{
+    claim_stdin, decode_lines, number_lines, read_to_end_limited, read_to_string_limited,
+    stdin_reader, BoxedReader, Stdin, StdinError, StdinOpts, Utf8Policy,
+};
+
+/// `stdin`, keeping its contents once read so each of its clones can read them again
+///
+/// Cloning a [`Stdin`](struct@Stdin) doesn't duplicate `stdin`, so reading a clone after `stdin`
+/// was read fails with [`StdinError::StdInRepeatedUse`]. The first read of a `SharedStdin`
+/// instead keeps the entire contents in memory, up to [`StdinOpts::max_bytes`] (or
+/// [`MAX_BYTES_ENV`](crate::MAX_BYTES_ENV)), and every clone reads them again:
+/// ```rust
+/// use clap_stdin::{SharedStdin, Source, StdinSession};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let _session = StdinSession::with_input("hello");
+/// let source = Source::SharedStdin(SharedStdin::new());
+/// let clone = source.clone();
+/// assert_eq!(source.contents()?, "hello");
+/// assert_eq!(clone.contents()?, "hello");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Args parsed with [`StdinOpts::share_stdin`] read a `Source::SharedStdin`.
+#[derive(Clone, Default)]
+pub struct SharedStdin {
+    contents: Arc<Mutex<Option<Arc<[u8]>>>>,
+}
+
+impl SharedStdin {
+    /// A `stdin` not read yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read from stdin, or the contents already read by a clone
+    pub fn read_string(&self) -> Result<String, StdinError> {
+        let opts = StdinOpts::default();
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        let max_bytes = max_bytes_limit(None)?;
+        let input = read_to_string_limited(reader, max_bytes, opts.utf8_policy())?;
+        Ok(opts.normalize(input))
+    }
+
+    /// Like [`Stdin::lines`], reading the contents already read by a clone if any
+    pub fn lines(&self) -> Result<impl Iterator<Item = io::Result<String>>, StdinError> {
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        Ok(decode_lines(reader, Utf8Policy::current_default()))
+    }
+
+    /// Like [`Stdin::lines_numbered`], reading the contents already read by a clone if any
+    pub fn lines_numbered(
+        &self,
+    ) -> Result<impl Iterator<Item = io::Result<(usize, String)>>, StdinError> {
+        let reader = self.reader(std::any::type_name::<Self>(), None)?;
+        Ok(number_lines(decode_lines(
+            reader,
+            Utf8Policy::current_default(),
+        )))
+    }
+
+    /// Reader over the contents already read by a clone, or over all of `stdin`, read at once
+    /// after preparing it as configured by `opts` and claiming it for `consumer`
+    pub(crate) fn reader(
+        &self,
+        consumer: &str,
+        opts: Option<&StdinOpts>,
+    ) -> Result<BoxedReader, StdinError> {
+        let mut kept = self.contents.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(contents) = &*kept {
+            return Ok(Box::new(Cursor::new(contents.clone())));
+        }
+        let contents = match prepare(consumer, opts)? {
+            Some(typed) => typed,
+            None => read_to_end_limited(stdin_reader(), max_bytes_limit(opts)?)?,
+        };
+        let contents: Arc<[u8]> = contents.into();
+        *kept = Some(contents.clone());
+        Ok(Box::new(Cursor::new(contents)))
+    }
+
+    /// The contents already read by a clone, if any
+    #[cfg(any(feature = "async", feature = "tokio"))]
+    pub(crate) fn kept(&self) -> Option<Arc<[u8]>> {
+        self.contents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Keep `contents` read from `stdin` for the clones
+    #[cfg(any(feature = "async", feature = "tokio"))]
+    pub(crate) fn keep(&self, contents: Vec<u8>) -> Arc<[u8]> {
+        let contents: Arc<[u8]> = contents.into();
+        *self.contents.lock().unwrap_or_else(PoisonError::into_inner) = Some(contents.clone());
+        contents
+    }
+}
+
+impl std::fmt::Debug for SharedStdin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedStdin")
+    }
+}
+
+impl Stdin {
+    /// Reader over `stdin`, after preparing it as configured by `opts` and claiming it for
+    /// `consumer`
+    pub(crate) fn reader(
+        &self,
+        consumer: &str,
+        opts: Option<&StdinOpts>,
+    ) -> Result<BoxedReader, StdinError> {
+        Ok(match prepare(consumer, opts)? {
+            Some(typed) => Box::new(Cursor::new(typed)),
+            None => stdin_reader(),
+        })
+    }
+}
+
+/// Prepare `stdin` as configured by `opts`, or just claim it for `consumer`
+fn prepare(consumer: &str, opts: Option<&StdinOpts>) -> Result<Option<Vec<u8>>, StdinError> {
+    match opts {
+        Some(opts) => opts.prepare_stdin(consumer),
+        None => {
+            claim_stdin(consumer)?;
+            Ok(None)
+        }
+    }
+}
+
+#[test]
+fn test_shared_stdin() {
+    use std::io::BufRead;
+
+    let _session = crate::StdinSession::with_input("first\nsecond\n");
+    let stdin = SharedStdin::new();
+    let clone = stdin.clone();
+    let lines = stdin.reader("test", None).unwrap().lines().count();
+    assert_eq!(lines, 2);
+    assert_eq!(clone.read_string().unwrap(), "first\nsecond\n");
+    assert_eq!(stdin.lines_numbered().unwrap().count(), 2);
+
+    // Not shared, so the clone can't read it again
+    let _session = crate::StdinSession::with_input("once");
+    let clone = Stdin.clone();
+    assert_eq!(Stdin.read_string().unwrap(), "once");
+    assert!(matches!(
+        clone.read_string(),
+        Err(StdinError::StdInRepeatedUse)
+    ));
+
+    let _session = crate::StdinSession::with_input("too large");
+    let opts = StdinOpts::new().max_bytes(3);
+    assert!(matches!(
+        SharedStdin::new().reader("test", Some(&opts)),
+        Err(StdinError::InputTooLarge(3))
+    ));
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+/// Statistics about how a wrapper type read its input
+///
+/// Available after the input was consumed, e.g. to report "read 1.2 GB from stdin in 3.4 s"
+/// in a verbose mode without wrapping the reader:
+#[cfg_attr(feature = "file", doc = "```rust,no_run")]
+#[cfg_attr(not(feature = "file"), doc = "```ignore")]
+/// use clap::Parser;
+/// use clap_stdin::FileOrStdin;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     input: FileOrStdin,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// let (input, stats) = args.input.contents_with_stats()?;
+/// eprintln!("read {} bytes in {:?}", stats.bytes_read, stats.duration);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReadStats {
+    /// Number of bytes read from the source
+    pub bytes_read: u64,
+    /// Number of values parsed from the input
+    pub items_parsed: usize,
+    /// Time spent between opening the source and the last read
+    pub duration: Duration,
+    /// Did the input exceed the configured [`StdinOpts::max_bytes`](crate::StdinOpts::max_bytes)
+    pub hit_limit: bool,
+    /// Digest of the bytes read, if requested with [`StdinOpts::digest`](crate::StdinOpts::digest)
+    #[cfg(any(feature = "sha2", feature = "blake3"))]
+    pub digest: Option<[u8; 32]>,
+}
@@ -0,0 +1,126 @@
+use std::str::FromStr;
+
+use super::{FileOrStdin, SourceKind, StdinError, StdinOpts};
+
+/// Wrapper struct to stream the lines of a file, or of `stdin`, after the args are parsed
+///
+/// Nothing is opened or read when the arg is parsed. Iterating yields each line (without its line
+/// ending) as it's read, so filter-style tools can process endless pipes in constant memory:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::StdinOrFileLines;
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     pattern: String,
+///     #[arg(default_value = "-")]
+///     input: StdinOrFileLines,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let args = Args::parse();
+/// for line in args.input {
+///     let line = line?;
+///     if line.contains(&args.pattern) {
+///         println!("{line}");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```sh
+/// $ tail -f app.log | ./example ERROR
+/// $ ./example ERROR app.log
+/// ```
+#[derive(Debug, Clone)]
+pub struct StdinOrFileLines {
+    input: FileOrStdin,
+}
+
+impl StdinOrFileLines {
+    /// A `value_parser` that reads the lines as configured by `opts` when iterated
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        let parse = FileOrStdin::with_opts(opts);
+        move |s| Ok(Self { input: parse(s)? })
+    }
+
+    /// Where the lines are read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        self.input.source_kind()
+    }
+
+    /// Are the lines read from stdin (shorthand for checking [`StdinOrFileLines::source_kind`])
+    pub fn is_stdin(&self) -> bool {
+        self.input.is_stdin()
+    }
+
+    /// The value passed to this arg (Either "-" for stdin or a filepath)
+    pub fn filename(&self) -> &str {
+        self.input.filename()
+    }
+}
+
+impl FromStr for StdinOrFileLines {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::with_opts(StdinOpts::default())(s)
+    }
+}
+
+impl IntoIterator for StdinOrFileLines {
+    type Item = Result<String, StdinError>;
+    type IntoIter = StdinLines;
+
+    /// Start reading the lines, opening the file or claiming `stdin`
+    fn into_iter(self) -> Self::IntoIter {
+        StdinLines {
+            lines: Box::new(self.input.lines()),
+        }
+    }
+}
+
+/// Iterator over the lines of a [`StdinOrFileLines`]
+pub struct StdinLines {
+    lines: Box<dyn Iterator<Item = Result<String, StdinError>> + Send>,
+}
+
+impl Iterator for StdinLines {
+    type Item = Result<String, StdinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next()
+    }
+}
+
+#[test]
+fn test_stdin_or_file_lines() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "first\r\n\nthird\n").unwrap();
+    let lines: StdinOrFileLines = tmp.path().to_str().unwrap().parse().unwrap();
+    assert_eq!(lines.source_kind(), SourceKind::File(tmp.path().into()));
+    let lines = lines.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(lines, ["first", "", "third"]);
+
+    let _session = crate::StdinSession::with_input("a\nb\n");
+    // Parsing doesn't claim stdin, only iterating does
+    let first: StdinOrFileLines = "-".parse().unwrap();
+    let second: StdinOrFileLines = "-".parse().unwrap();
+    assert!(first.is_stdin());
+    let mut lines = first.into_iter();
+    assert_eq!(lines.next().unwrap().unwrap(), "a");
+    assert_eq!(lines.next().unwrap().unwrap(), "b");
+    assert!(lines.next().is_none());
+    assert!(matches!(
+        second.into_iter().next(),
+        Some(Err(StdinError::StdInRepeatedUse))
+    ));
+
+    let lines: StdinOrFileLines = "missing.txt".parse().unwrap();
+    assert!(lines.into_iter().next().unwrap().is_err());
+}
@@ -0,0 +1,335 @@
+//! Wrappers for structured documents (JSON, JSON Lines, YAML and TOML) read from a file, or
+//! from `stdin`
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use super::{FileOrStdin, SourceKind, StdinError, StdinOpts};
+
+/// Implement the wrapper methods for a document format, read with the given
+/// `FileOrStdin::deserialize_*` method
+macro_rules! document_wrapper {
+    ($name:ident, $deserialize:ident) => {
+        impl<T: DeserializeOwned> $name<T> {
+            fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+                let input = FileOrStdin::<String>::with_opts(opts.clone())(s)?;
+                let source = input.source_kind();
+                let inner = input.$deserialize()?;
+                Ok(Self { inner, source })
+            }
+
+            /// A `value_parser` that reads the document as configured by `opts`
+            ///
+            /// See [`StdinOpts`] for the available options
+            pub fn with_opts(
+                opts: StdinOpts,
+            ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+                move |s| Self::parse_with_opts(s, &opts)
+            }
+        }
+
+        impl<T> $name<T> {
+            /// Where this value was read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+            pub fn source_kind(&self) -> SourceKind {
+                self.source.clone()
+            }
+
+            /// Was this value read from stdin (shorthand for checking the `source_kind`)
+            pub fn is_stdin(&self) -> bool {
+                self.source == SourceKind::Stdin
+            }
+
+            /// Extract the value from the wrapper
+            pub fn into_inner(self) -> T {
+                self.inner
+            }
+        }
+
+        impl<T: DeserializeOwned> FromStr for $name<T> {
+            type Err = StdinError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse_with_opts(s, &StdinOpts::default())
+            }
+        }
+
+        impl<T> std::ops::Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &Self::Target {
+                &self.inner
+            }
+        }
+
+        impl<T> std::ops::DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.inner
+            }
+        }
+    };
+}
+
+/// Wrapper struct for a JSON value read from a file, or from `stdin`
+///
+/// The file (or `stdin`) is read and deserialized while the args are parsed, so a malformed
+/// document is reported as an invalid arg value. Like any clap value, `T` must be `Clone`:
+/// ```rust,no_run
+/// use clap::Parser;
+/// use clap_stdin::Json;
+///
+/// #[derive(Debug, Clone, serde::Deserialize)]
+/// struct Config {
+///     name: String,
+/// }
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     config: Json<Config>,
+/// }
+///
+/// let args = Args::parse();
+/// println!("name={}", args.config.name);
+/// ```
+///
+/// ```sh
+/// $ ./example --config config.json
+/// $ cat config.json | ./example --config -
+/// ```
+#[derive(Debug, Clone)]
+pub struct Json<T> {
+    inner: T,
+    source: SourceKind,
+}
+
+document_wrapper!(Json, deserialize_json);
+
+/// Wrapper struct for the JSON values of [JSON Lines](https://jsonlines.org) (NDJSON) input read
+/// from a file, or from `stdin`
+///
+/// Each line is deserialized as a separate JSON document, while blank lines are skipped. Like
+/// [`Json`], the input is deserialized while the args are parsed, and the first malformed record
+/// is reported with its line number:
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{JsonLines, StdinSession};
+///
+/// #[derive(Debug, Clone, serde::Deserialize)]
+/// struct Event {
+///     id: u32,
+/// }
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     events: JsonLines<Event>,
+/// }
+///
+/// let _session = StdinSession::with_input("{\"id\": 1}\n{\"id\": 2}\n");
+/// let args = Args::parse_from(["example", "-"]);
+/// assert_eq!(args.events.len(), 2);
+///
+/// let _session = StdinSession::with_input("{\"id\": 1}\n{\"id\": \"two\"}\n");
+/// let error = Args::try_parse_from(["example", "-"]).unwrap_err();
+/// assert!(error.to_string().contains("line 2"));
+/// ```
+///
+/// ```sh
+/// $ ./example events.jsonl
+/// $ cat events.jsonl | ./example -
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonLines<T> {
+    inner: Vec<T>,
+    source: SourceKind,
+}
+
+impl<T: DeserializeOwned> JsonLines<T> {
+    fn parse_with_opts(s: &str, opts: &StdinOpts) -> Result<Self, StdinError> {
+        let input = FileOrStdin::<String>::with_opts(opts.clone())(s)?;
+        let source = input.source_kind();
+        let mut inner = Vec::new();
+        for line in input.lines_numbered() {
+            let (n, line) = line.map_err(StdinError::from_io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let item = serde_json::from_str(&line)
+                .map_err(|e| StdinError::Deserialize(format!("line {n}: {e}")))?;
+            inner.push(item);
+        }
+        Ok(Self { inner, source })
+    }
+
+    /// A `value_parser` that reads the records as configured by `opts`
+    ///
+    /// See [`StdinOpts`] for the available options
+    pub fn with_opts(
+        opts: StdinOpts,
+    ) -> impl Fn(&str) -> Result<Self, StdinError> + Clone + Send + Sync + 'static {
+        move |s| Self::parse_with_opts(s, &opts)
+    }
+}
+
+impl<T> JsonLines<T> {
+    /// Where these records were read from: [`SourceKind::Stdin`] or [`SourceKind::File`]
+    pub fn source_kind(&self) -> SourceKind {
+        self.source.clone()
+    }
+
+    /// Were these records read from stdin (shorthand for checking the `source_kind`)
+    pub fn is_stdin(&self) -> bool {
+        self.source == SourceKind::Stdin
+    }
+
+    /// Extract the records from the wrapper
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T: DeserializeOwned> FromStr for JsonLines<T> {
+    type Err = StdinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_opts(s, &StdinOpts::default())
+    }
+}
+
+impl<T> std::ops::Deref for JsonLines<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for JsonLines<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> IntoIterator for JsonLines<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+/// Wrapper struct for a YAML value read from a file, or from `stdin`
+///
+/// Like [`Json`], for YAML documents:
+/// ```sh
+/// $ ./example --config config.yaml
+/// $ cat config.yaml | ./example --config -
+/// ```
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone)]
+pub struct Yaml<T> {
+    inner: T,
+    source: SourceKind,
+}
+
+#[cfg(feature = "yaml")]
+document_wrapper!(Yaml, deserialize_yaml);
+
+/// Wrapper struct for a TOML value read from a file, or from `stdin`
+///
+/// Like [`Json`], for TOML documents:
+/// ```sh
+/// $ ./example --config config.toml
+/// $ cat config.toml | ./example --config -
+/// ```
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone)]
+pub struct Toml<T> {
+    inner: T,
+    source: SourceKind,
+}
+
+#[cfg(feature = "toml")]
+document_wrapper!(Toml, deserialize_toml);
+
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+struct Config {
+    name: String,
+    retries: u32,
+}
+
+#[test]
+fn test_json() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, r#"{"name": "file", "retries": 3}"#).unwrap();
+    let config: Json<Config> = tmp.path().to_str().unwrap().parse().unwrap();
+    assert!(!config.is_stdin());
+    assert_eq!(config.name, "file");
+    assert_eq!(config.retries, 3);
+
+    let _session = crate::StdinSession::with_input(r#"{"name": "stdin", "retries": 1}"#);
+    let config: Json<Config> = "-".parse().unwrap();
+    assert!(config.is_stdin());
+    assert_eq!(
+        config.into_inner(),
+        Config {
+            name: "stdin".to_string(),
+            retries: 1
+        }
+    );
+
+    let _session = crate::StdinSession::with_input(r#"{"name": 1}"#);
+    assert!(matches!(
+        "-".parse::<Json<Config>>(),
+        Err(StdinError::Deserialize(_))
+    ));
+}
+
+#[test]
+fn test_json_lines() {
+    let _session = crate::StdinSession::with_input(
+        "{\"name\": \"a\", \"retries\": 1}\r\n\n{\"name\": \"b\", \"retries\": 2}\n",
+    );
+    let configs: JsonLines<Config> = "-".parse().unwrap();
+    assert!(configs.is_stdin());
+    assert_eq!(configs.len(), 2);
+    assert_eq!(configs[1].name, "b");
+
+    let _session =
+        crate::StdinSession::with_input("{\"name\": \"a\", \"retries\": 1}\n\n{\"name\": 1}\n");
+    let error = "-".parse::<JsonLines<Config>>().unwrap_err();
+    assert!(matches!(error, StdinError::Deserialize(e) if e.starts_with("line 3:")));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml() {
+    let _session = crate::StdinSession::with_input("name: stdin\nretries: 2\n");
+    let config: Yaml<Config> = "-".parse().unwrap();
+    assert!(config.is_stdin());
+    assert_eq!(config.name, "stdin");
+    assert_eq!(config.retries, 2);
+
+    let _session = crate::StdinSession::with_input("name: [");
+    assert!(matches!(
+        "-".parse::<Yaml<Config>>(),
+        Err(StdinError::Deserialize(_))
+    ));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml() {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(&tmp, "name = \"file\"\nretries = 5\n").unwrap();
+    let config: Toml<Config> = tmp.path().to_str().unwrap().parse().unwrap();
+    assert_eq!(config.source_kind(), SourceKind::File(tmp.path().into()));
+    assert_eq!(config.retries, 5);
+
+    let _session = crate::StdinSession::with_input("name = ");
+    assert!(matches!(
+        "-".parse::<Toml<Config>>(),
+        Err(StdinError::Deserialize(_))
+    ));
+}
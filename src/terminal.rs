@@ -0,0 +1,162 @@
+#[cfg(feature = "maybe-stdin")]
+use std::fs::File;
+use std::io::{self, BufRead};
+#[cfg(feature = "maybe-stdin")]
+use std::io::{BufReader, Write};
+
+#[cfg(feature = "crossterm")]
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+use super::guard::claim_terminal;
+#[cfg(feature = "crossterm")]
+use super::guard::TerminalClaim;
+use super::session::{stdin_is_terminal, stdin_reader};
+#[cfg(any(feature = "maybe-stdin", feature = "crossterm"))]
+use super::StdinError;
+
+/// Open the controlling terminal for interactive input
+///
+/// This is independent of `stdin`, which may be piped (and already consumed by an argument)
+#[cfg(feature = "maybe-stdin")]
+pub(crate) fn open_terminal() -> io::Result<BufReader<File>> {
+    #[cfg(windows)]
+    let path = "CONIN$";
+    #[cfg(not(windows))]
+    let path = "/dev/tty";
+    File::open(path).map(BufReader::new)
+}
+
+/// Write `prompt` to `stderr` and read a line of input from the terminal,
+/// without the trailing newline
+#[cfg(feature = "maybe-stdin")]
+pub(crate) fn prompt_line(prompt: &str) -> Result<String, StdinError> {
+    let _claim = claim_terminal()?;
+    let mut terminal = open_terminal().map_err(|_| StdinError::NoTerminal)?;
+    let mut stderr = io::stderr().lock();
+    write!(stderr, "{prompt}")?;
+    stderr.flush()?;
+    let mut line = String::new();
+    terminal.read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+/// Write `prompt` to the terminal and read a line of input from it without echoing it
+#[cfg(feature = "secret")]
+pub(crate) fn prompt_secret(prompt: &str) -> Result<String, StdinError> {
+    let _claim = claim_terminal()?;
+    rpassword::prompt_password(prompt).map_err(|_| StdinError::NoTerminal)
+}
+
+/// Ask the user to confirm with `y` or `yes` on the terminal
+#[cfg(feature = "maybe-stdin")]
+pub(crate) fn confirm(prompt: &str) -> Result<(), StdinError> {
+    let answer = prompt_line(&format!("{prompt} [y/N] "))?;
+    if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+        Ok(())
+    } else {
+        Err(StdinError::NotConfirmed)
+    }
+}
+
+/// The controlling terminal in raw mode, for reading single keypresses
+///
+/// Keys are read from the terminal, not from `stdin`, so this works while `stdin` is piped into
+/// an argument. Only one interactive prompt may use the terminal at a time: enabling raw mode
+/// while another [`RawTerminal`] (or a confirmation prompt) is active fails with
+/// [`StdinError::TerminalBusy`]. The previous terminal mode is restored on drop.
+///
+/// Raw mode disables signal handling, so `Ctrl+C` is returned as a key like any other.
+///
+/// ```rust,no_run
+/// use clap_stdin::RawTerminal;
+/// use crossterm::event::KeyCode;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// eprint!("Continue? [y/n] ");
+/// let key = RawTerminal::read_single_key()?;
+/// if key.code == KeyCode::Char('y') {
+///     // ...
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "crossterm")]
+#[derive(Debug)]
+pub struct RawTerminal {
+    _claim: TerminalClaim,
+}
+
+#[cfg(feature = "crossterm")]
+impl RawTerminal {
+    /// Claim the terminal and switch it to raw (non-canonical, no echo) mode
+    pub fn enable() -> Result<Self, StdinError> {
+        let claim = claim_terminal()?;
+        crossterm::terminal::enable_raw_mode().map_err(|_| StdinError::NoTerminal)?;
+        Ok(Self { _claim: claim })
+    }
+
+    /// Wait for the next keypress
+    pub fn read_key(&mut self) -> Result<KeyEvent, StdinError> {
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    return Ok(key);
+                }
+            }
+        }
+    }
+
+    /// Read a single keypress in raw mode, restoring the terminal afterwards
+    pub fn read_single_key() -> Result<KeyEvent, StdinError> {
+        Self::enable()?.read_key()
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+/// Read lines from `reader` until a line consisting only of `terminator` (or EOF)
+///
+/// The terminator line itself is not included in the result
+pub(crate) fn read_until_terminator(
+    mut reader: impl BufRead,
+    terminator: &str,
+) -> io::Result<String> {
+    let mut input = String::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        if line.trim_end_matches(['\r', '\n']) == terminator {
+            break;
+        }
+        input.push_str(&line);
+    }
+    Ok(input)
+}
+
+/// Read multi-line input from `stdin`
+///
+/// If `stdin` is a terminal, a hint about how to end the input is shown on `stderr`, and reading
+/// stops at a line consisting only of `terminator`. Otherwise the entire input is read.
+pub(crate) fn read_stdin_multiline(terminator: &str) -> io::Result<String> {
+    if !stdin_is_terminal() {
+        return io::read_to_string(stdin_reader());
+    }
+    eprintln!("(enter a line with only '{terminator}' to finish)");
+    read_until_terminator(io::stdin().lock(), terminator)
+}
+
+#[test]
+fn test_read_until_terminator() {
+    let input = io::Cursor::new("a: 1\nb: 2\r\n.\nignored\n");
+    assert_eq!(read_until_terminator(input, ".").unwrap(), "a: 1\nb: 2\r\n");
+    let input = io::Cursor::new("a\n..\nEOF");
+    assert_eq!(read_until_terminator(input, "EOF").unwrap(), "a\n..\n");
+}
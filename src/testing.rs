@@ -0,0 +1,307 @@
+//! Helpers for testing CLIs built with `clap-stdin`
+//!
+//! With the `testing` feature enabled, [`Source`], [`MaybeStdin`] and [`MaybeStdinVec`]
+//! implement [`arbitrary::Arbitrary`], and this module provides [`proptest`] strategies
+//! for generating values and raw argument strings.
+//!
+//! Parsers can be unit-tested without spawning a process: [`with_stdin`] runs a closure where
+//! `stdin` holds a given input.
+//!
+//! For end-to-end tests, [`CommandStdinExt`] extends [`assert_cmd::Command`] to pipe
+//! stdin fixtures and capture which wrapper type consumed `stdin` (see [`StdinProvenance`]).
+//!
+//! ```rust
+//! use proptest::strategy::{Strategy, ValueTree};
+//! use proptest::test_runner::TestRunner;
+//! use clap_stdin::{testing, Source};
+//!
+//! let mut runner = TestRunner::default();
+//! let arg = testing::source_arg().new_tree(&mut runner).unwrap().current();
+//! let source: Source = arg.parse().unwrap();
+//! assert_eq!(matches!(source, Source::Stdin(_)), arg == "-");
+//! ```
+use std::io;
+use std::path::Path;
+use std::process::Output;
+
+use arbitrary::{Arbitrary, Unstructured};
+use assert_cmd::Command;
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+
+use crate::{MaybeStdin, MaybeStdinVec, Source, SourceKind, StdinSession};
+
+/// When this environment variable is set, binaries built with the `testing` feature report the
+/// type that consumes `stdin` on `stderr` (prefixed with [`PROVENANCE_PREFIX`]), so end-to-end
+/// tests can assert which argument read it
+pub const PROVENANCE_ENV: &str = "CLAP_STDIN_PROVENANCE";
+
+/// Prefix of the `stderr` lines written when [`PROVENANCE_ENV`] is set
+pub const PROVENANCE_PREFIX: &str = "clap-stdin: stdin consumed by ";
+
+/// Prefix of the `stderr` lines written when [`PROVENANCE_ENV`] is set and an arg value is used
+/// as the contents, see [`StdinOpts::literal_fallback`](crate::StdinOpts::literal_fallback)
+pub const PROVENANCE_LITERAL_PREFIX: &str = "clap-stdin: literal value used by ";
+
+impl<'a> Arbitrary<'a> for Source {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        if u.arbitrary()? {
+            Ok(Source::stdin())
+        } else {
+            Ok(Source::Arg(u.arbitrary()?))
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for SourceKind {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=2)? {
+            0 => SourceKind::Stdin,
+            1 => SourceKind::Literal,
+            _ => SourceKind::File(String::arbitrary(u)?.into()),
+        })
+    }
+}
+
+impl<'a, T> Arbitrary<'a> for MaybeStdin<T>
+where
+    T: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(MaybeStdin::from_value(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl<'a, T, const D: char, const STDIN_DELIMITED: bool> Arbitrary<'a>
+    for MaybeStdinVec<T, D, STDIN_DELIMITED>
+where
+    T: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(MaybeStdinVec::from_items(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+/// Strategy producing raw argument values, either the stdin alias `-` or any other string
+pub fn source_arg() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::from("-")),
+        any::<String>().prop_filter("not the stdin alias", |s| s != "-"),
+    ]
+}
+
+/// Strategy producing a [`Source`] that is either stdin or an arbitrary arg value
+pub fn source() -> impl Strategy<Value = Source> {
+    prop_oneof![Just(Source::stdin()), any::<String>().prop_map(Source::Arg),]
+}
+
+/// Strategy producing the [`SourceKind`] of a parsed value: either stdin or a literal arg
+pub fn value_kind() -> impl Strategy<Value = SourceKind> {
+    prop_oneof![Just(SourceKind::Stdin), Just(SourceKind::Literal)]
+}
+
+/// Strategy producing a [`MaybeStdin`] wrapping values from `value`, with a stdin or
+/// literal [`SourceKind`]
+pub fn maybe_stdin<S>(value: S) -> impl Strategy<Value = MaybeStdin<S::Value>>
+where
+    S: Strategy,
+{
+    (value, value_kind()).prop_map(|(value, kind)| MaybeStdin::from_value(value, kind))
+}
+
+/// Strategy producing a [`MaybeStdinVec`] with items from `item` and a length in `size`
+pub fn maybe_stdin_vec<S, const D: char>(
+    item: S,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = MaybeStdinVec<S::Value, D>>
+where
+    S: Strategy,
+{
+    (vec(item, size), value_kind()).prop_map(|(items, kind)| MaybeStdinVec::from_items(items, kind))
+}
+
+/// Run `f` with `stdin` holding `input`, instead of the process `stdin`
+///
+/// Within `f`, args given `-` read `input` (once, like a real pipe), on the current thread only.
+/// This is a shorthand for holding a [`StdinSession::with_input`] while `f` runs.
+/// ```rust
+/// use clap::Parser;
+/// use clap_stdin::{testing, MaybeStdin};
+///
+/// #[derive(Debug, Parser)]
+/// struct Args {
+///     value: MaybeStdin<u32>,
+/// }
+///
+/// let args = testing::with_stdin("42\n", || Args::try_parse_from(["example", "-"])).unwrap();
+/// assert_eq!(*args.value, 42);
+/// ```
+pub fn with_stdin<R>(input: impl Into<Vec<u8>>, f: impl FnOnce() -> R) -> R {
+    let _session = StdinSession::with_input(input);
+    f()
+}
+
+/// Reset the "stdin has been read" state, so the next argument given `-` can read `stdin` again
+///
+/// For test suites that parse args reading `stdin` in sequential tests. Prefer holding a
+/// [`StdinGuard::scoped`](crate::StdinGuard::scoped) handle (or a [`StdinSession`]) in each test,
+/// which also works when tests run in parallel threads ([`with_stdin`] does so already).
+pub fn reset_stdin_guard() {
+    crate::guard::reset_stdin_read();
+}
+
+/// Extension methods for [`assert_cmd::Command`] to test `stdin` handling end-to-end
+///
+/// Provenance is only reported by binaries built with the `testing` feature (E.g. through a
+/// feature of the binary's crate that enables `clap-stdin/testing`).
+/// ```rust,no_run
+/// use assert_cmd::Command;
+/// use clap_stdin::testing::CommandStdinExt;
+/// use clap_stdin::MaybeStdin;
+///
+/// let provenance = Command::cargo_bin("my_cli")
+///     .unwrap()
+///     .args(["--value", "-"])
+///     .feed_stdin("42")
+///     .stdin_provenance()?;
+/// assert!(provenance.consumed_by::<MaybeStdin<u32>>());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub trait CommandStdinExt {
+    /// Write `input` to the command's stdin and enable provenance reporting
+    fn feed_stdin(&mut self, input: impl Into<Vec<u8>>) -> &mut Self;
+
+    /// Write the contents of the fixture file at `path` to the command's stdin
+    /// and enable provenance reporting
+    fn feed_stdin_fixture(&mut self, path: impl AsRef<Path>) -> io::Result<&mut Self>;
+
+    /// Run the command, capturing its output and which types consumed `stdin`
+    fn stdin_provenance(&mut self) -> io::Result<StdinProvenance>;
+}
+
+impl CommandStdinExt for Command {
+    fn feed_stdin(&mut self, input: impl Into<Vec<u8>>) -> &mut Self {
+        self.env(PROVENANCE_ENV, "1").write_stdin(input)
+    }
+
+    fn feed_stdin_fixture(&mut self, path: impl AsRef<Path>) -> io::Result<&mut Self> {
+        let input = std::fs::read(path)?;
+        Ok(self.feed_stdin(input))
+    }
+
+    fn stdin_provenance(&mut self) -> io::Result<StdinProvenance> {
+        let output = self.env(PROVENANCE_ENV, "1").output()?;
+        Ok(StdinProvenance::from_output(output))
+    }
+}
+
+/// Output of a command run with provenance reporting, recording which types consumed `stdin`
+#[derive(Debug)]
+pub struct StdinProvenance {
+    output: Output,
+    consumers: Vec<String>,
+    literals: Vec<String>,
+}
+
+impl StdinProvenance {
+    /// Collect the provenance lines reported on `stderr` of a finished command
+    pub fn from_output(output: Output) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reported = |prefix| {
+            stderr
+                .lines()
+                .filter_map(|line| line.strip_prefix(prefix))
+                .map(String::from)
+                .collect()
+        };
+        let consumers = reported(PROVENANCE_PREFIX);
+        let literals = reported(PROVENANCE_LITERAL_PREFIX);
+        Self {
+            output,
+            consumers,
+            literals,
+        }
+    }
+
+    /// Type names of every consumer of `stdin`, in the order they read it
+    pub fn consumers(&self) -> &[String] {
+        &self.consumers
+    }
+
+    /// Was `stdin` consumed at all
+    pub fn is_consumed(&self) -> bool {
+        !self.consumers.is_empty()
+    }
+
+    /// Was `stdin` consumed by the type `T` (E.g. `MaybeStdin<u32>`)
+    pub fn consumed_by<T: ?Sized>(&self) -> bool {
+        let name = std::any::type_name::<T>();
+        self.consumers.iter().any(|c| c == name)
+    }
+
+    /// Type names of every arg that used its value as the contents, instead of reading a file
+    /// (see [`StdinOpts::literal_fallback`](crate::StdinOpts::literal_fallback))
+    pub fn literal_fallbacks(&self) -> &[String] {
+        &self.literals
+    }
+
+    /// Did an arg of the type `T` (E.g. `FileOrStdin`) use its value as the contents
+    pub fn used_literal_fallback<T: ?Sized>(&self) -> bool {
+        let name = std::any::type_name::<T>();
+        self.literals.iter().any(|c| c == name)
+    }
+
+    /// The captured output of the command
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+}
+
+/// The `stderr` line reported when `T` consumes `stdin`, for use with `predicates::str::contains`
+pub fn provenance_line<T: ?Sized>() -> String {
+    format!("{PROVENANCE_PREFIX}{}", std::any::type_name::<T>())
+}
+
+#[test]
+fn test_with_stdin() {
+    let value: MaybeStdin<u32> = with_stdin("7", || "-".parse()).unwrap();
+    assert!(value.is_stdin());
+    assert_eq!(*value, 7);
+    let values: MaybeStdinVec<u32> = with_stdin("1\n2\n", || "-".parse()).unwrap();
+    assert_eq!(*values, [1, 2]);
+}
+
+#[test]
+fn test_reset_stdin_guard() {
+    let guard = crate::StdinGuard::scoped();
+    crate::guard::claim_stdin("test").unwrap();
+    assert!(guard.is_consumed());
+    reset_stdin_guard();
+    assert!(!guard.is_consumed());
+}
+
+#[test]
+fn test_arbitrary_source() {
+    let mut u = Unstructured::new(&[0, 3, b'a', b'b', b'c']);
+    assert!(matches!(Source::arbitrary(&mut u), Ok(Source::Arg(_))));
+    let mut u = Unstructured::new(&[1]);
+    assert!(matches!(Source::arbitrary(&mut u), Ok(Source::Stdin(_))));
+}
+
+#[test]
+fn test_provenance_from_output() {
+    let stderr = format!(
+        "{PROVENANCE_PREFIX}{}\nwarning: unrelated\n{PROVENANCE_LITERAL_PREFIX}{}\n",
+        std::any::type_name::<MaybeStdin<u32>>(),
+        std::any::type_name::<MaybeStdinVec<u32>>(),
+    );
+    let provenance = StdinProvenance::from_output(Output {
+        status: Default::default(),
+        stdout: Vec::new(),
+        stderr: stderr.into_bytes(),
+    });
+    assert!(provenance.consumed_by::<MaybeStdin<u32>>());
+    assert_eq!(provenance.consumers().len(), 1);
+    assert!(provenance.used_literal_fallback::<MaybeStdinVec<u32>>());
+    assert!(!provenance.used_literal_fallback::<MaybeStdin<u32>>());
+}
@@ -0,0 +1,58 @@
+//! Transcoding of text input to UTF-8, see [`StdinOpts::encoding`](crate::StdinOpts::encoding)
+use std::io::BufReader;
+
+use encoding_rs::Encoding;
+use encoding_rs_io::DecodeReaderBytesBuilder;
+
+use super::BoxedReader;
+
+/// Wrap `reader` in a decoder transcoding its contents to UTF-8
+///
+/// The encoding is taken from a byte order mark (which is removed), or else is `encoding`.
+/// Without either, the contents are read unchanged.
+pub(crate) fn transcoded(reader: BoxedReader, encoding: Option<&'static Encoding>) -> BoxedReader {
+    debug_log!("transcoding input from {:?}", encoding.map(Encoding::name));
+    Box::new(BufReader::new(
+        DecodeReaderBytesBuilder::new()
+            .encoding(encoding)
+            .bom_override(true)
+            .build(reader),
+    ))
+}
+
+#[cfg(test)]
+fn transcode(input: &[u8], encoding: Option<&'static Encoding>) -> String {
+    let reader = transcoded(Box::new(std::io::Cursor::new(input.to_vec())), encoding);
+    std::io::read_to_string(reader).unwrap()
+}
+
+#[test]
+fn test_bom() {
+    assert_eq!(
+        transcode(b"\xff\xfec\x00a\x00f\x00\xe9\x00", None),
+        "caf\u{e9}"
+    );
+    assert_eq!(
+        transcode(b"\xfe\xff\x00c\x00a\x00f\x00\xe9", None),
+        "caf\u{e9}"
+    );
+    assert_eq!(transcode(b"\xef\xbb\xbfcaf\xc3\xa9", None), "caf\u{e9}");
+    assert_eq!(transcode(b"plain", None), "plain");
+}
+
+#[test]
+fn test_explicit_encoding() {
+    assert_eq!(
+        transcode(b"caf\xe9", Some(encoding_rs::WINDOWS_1252)),
+        "caf\u{e9}"
+    );
+    // A byte order mark takes precedence over the configured encoding
+    assert_eq!(
+        transcode(b"\xff\xfeo\x00k\x00", Some(encoding_rs::WINDOWS_1252)),
+        "ok"
+    );
+
+    let _session = crate::StdinSession::with_input(*b"\xff\xfe4\x002\x00\n\x00");
+    let parse = crate::MaybeStdin::<u32>::with_opts(crate::StdinOpts::new().detect_encoding());
+    assert_eq!(*parse("-").unwrap(), 42);
+}
@@ -0,0 +1,110 @@
+//! [`clap::builder::TypedValueParser`] for the wrapper types, see [`StdinValueParser`]
+use std::ffi::OsStr;
+use std::sync::Arc;
+
+use clap::error::ErrorKind;
+
+use super::StdinError;
+
+/// A `with_opts` parser, shared between the clones of a [`StdinValueParser`]
+type ParseFn<T> = Arc<dyn Fn(&str) -> Result<T, StdinError> + Send + Sync>;
+
+/// A clap value parser for the wrapper types, reporting failures with the name of the arg
+///
+/// With the `clap` feature, `value_parser!` (and so the derive API) uses it for
+/// [`MaybeStdin`](crate::MaybeStdin) and the other value wrappers. Builder API users can wrap a
+/// `with_opts` parser in it to configure each arg, and still get errors like
+/// `invalid value '-' for '--count <COUNT>': invalid digit found in string`:
+/// ```rust
+/// use clap::{Arg, Command};
+/// use clap_stdin::{MaybeStdin, StdinOpts, StdinValueParser};
+///
+/// let command = Command::new("example").arg(
+///     Arg::new("count")
+///         .long("count")
+///         .value_parser(StdinValueParser::new(MaybeStdin::<u32>::with_opts(
+///             StdinOpts::new().sentinel("@-"),
+///         ))),
+/// );
+/// let matches = command.try_get_matches_from(["example", "--count", "42"]).unwrap();
+/// assert_eq!(**matches.get_one::<MaybeStdin<u32>>("count").unwrap(), 42);
+/// ```
+pub struct StdinValueParser<T> {
+    parse: ParseFn<T>,
+}
+
+impl<T> StdinValueParser<T> {
+    /// Parse values with `parse`, E.g. the `with_opts` parser of a wrapper type
+    pub fn new(parse: impl Fn(&str) -> Result<T, StdinError> + Send + Sync + 'static) -> Self {
+        Self {
+            parse: Arc::new(parse),
+        }
+    }
+}
+
+impl<T> Clone for StdinValueParser<T> {
+    fn clone(&self) -> Self {
+        Self {
+            parse: self.parse.clone(),
+        }
+    }
+}
+
+impl<T> clap::builder::TypedValueParser for StdinValueParser<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Value = T;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let Some(value) = value.to_str() else {
+            return Err(clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd));
+        };
+        (self.parse)(value).map_err(|e| {
+            let arg = arg.map_or_else(|| "...".to_owned(), |arg| arg.to_string());
+            clap::Error::raw(
+                error_kind(&e),
+                format!("invalid value '{value}' for '{arg}': {e}\n"),
+            )
+            .with_cmd(cmd)
+        })
+    }
+}
+
+/// The kind of clap error to report a [`StdinError`] as
+pub(crate) fn error_kind(e: &StdinError) -> ErrorKind {
+    match e {
+        StdinError::StdIn(_) | StdinError::File(..) => ErrorKind::Io,
+        _ => ErrorKind::ValueValidation,
+    }
+}
+
+#[cfg(feature = "maybe-stdin")]
+#[test]
+fn test_value_parser() {
+    use crate::MaybeStdin;
+
+    let command = clap::Command::new("example").arg(
+        clap::Arg::new("count")
+            .long("count")
+            .value_parser(clap::value_parser!(MaybeStdin<u32>)),
+    );
+    let matches = command
+        .clone()
+        .try_get_matches_from(["example", "--count", "42"])
+        .unwrap();
+    assert_eq!(**matches.get_one::<MaybeStdin<u32>>("count").unwrap(), 42);
+
+    let error = command
+        .try_get_matches_from(["example", "--count", "many"])
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    assert!(error
+        .to_string()
+        .contains("invalid value 'many' for '--count <count>'"));
+}
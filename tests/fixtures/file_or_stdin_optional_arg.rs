@@ -9,7 +9,7 @@ struct Args {
     second: Option<FileOrStdin<u32>>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() {
     let args = Args::parse();
     println!(
@@ -21,12 +21,12 @@ fn main() {
 
 #[cfg(feature = "test_bin_tokio")]
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> Result<(), String> {
     let args = Args::parse();
-    println!(
-        "FIRST: {}, SECOND: {:?}",
-        args.first,
-        args.second
-            .map(|second| second.contents_async().await.unwrap()),
-    );
+    let second = match args.second {
+        Some(second) => Some(second.contents_async().await.map_err(|e| format!("{e}"))?),
+        None => None,
+    };
+    println!("FIRST: {}, SECOND: {:?}", args.first, second);
+    Ok(())
 }
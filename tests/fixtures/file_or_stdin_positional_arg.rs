@@ -10,7 +10,7 @@ struct Args {
     second: Option<String>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
@@ -23,11 +23,15 @@ fn main() -> Result<(), String> {
 
 #[cfg(feature = "test_bin_tokio")]
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
         "FIRST: {}; SECOND: {:?}",
-        args.first.contents_async().await?,
+        args.first
+            .contents_async()
+            .await
+            .map_err(|e| format!("{e}"))?,
         args.second
     );
+    Ok(())
 }
@@ -8,7 +8,7 @@ struct Args {
     second: MaybeStdin<u32>,
 }
 
-#[cfg(feature = "test_bin")]
+#[cfg(not(feature = "test_bin_tokio"))]
 fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
@@ -22,11 +22,15 @@ fn main() -> Result<(), String> {
 
 #[cfg(feature = "test_bin_tokio")]
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> Result<(), String> {
     let args = Args::parse();
     println!(
         "FIRST: {}; SECOND: {}",
-        args.first.contents_async()?,
+        args.first
+            .contents_async()
+            .await
+            .map_err(|e| format!("{e}"))?,
         args.second
     );
+    Ok(())
 }
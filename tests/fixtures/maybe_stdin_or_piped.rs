@@ -0,0 +1,14 @@
+use clap::Parser;
+
+use clap_stdin::{MaybeStdin, StdinOpts};
+
+#[derive(Debug, Parser)]
+struct Args {
+    value: Option<MaybeStdin<u32>>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let value = MaybeStdin::or_piped_stdin(args.value, &StdinOpts::default()).unwrap();
+    println!("{:?}", value.map(MaybeStdin::into_inner));
+}
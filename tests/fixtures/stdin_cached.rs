@@ -0,0 +1,28 @@
+use clap::Parser;
+
+use clap_stdin::{FileOrStdin, MaybeStdin, Stdin, StdinSession};
+
+#[derive(Debug, Parser)]
+struct Args {
+    first: MaybeStdin<String>,
+    second: FileOrStdin,
+}
+
+fn main() -> Result<(), String> {
+    Stdin::enable_cache();
+    let args = Args::try_parse().map_err(|e| format!("{e}"))?;
+    println!(
+        "FIRST: {}; SECOND: {}",
+        args.first,
+        args.second.contents().map_err(|e| format!("{e}"))?
+    );
+    // A session caches its own input, not the process stdin
+    let _session = StdinSession::with_input("SESSION");
+    let args = Args::try_parse().map_err(|e| format!("{e}"))?;
+    println!(
+        "FIRST: {}; SECOND: {}",
+        args.first,
+        args.second.contents().map_err(|e| format!("{e}"))?
+    );
+    Ok(())
+}
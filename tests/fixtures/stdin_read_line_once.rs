@@ -0,0 +1,8 @@
+use clap_stdin::Stdin;
+
+fn main() -> Result<(), String> {
+    let token = Stdin.read_line_once().map_err(|e| format!("{e}"))?;
+    let payload = Stdin.read_string().map_err(|e| format!("{e}"))?;
+    println!("TOKEN: {token:?}; PAYLOAD: {payload:?}");
+    Ok(())
+}
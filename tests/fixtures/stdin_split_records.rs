@@ -0,0 +1,20 @@
+use clap::Parser;
+
+use clap_stdin::{FileOrStdin, MaybeStdin, Stdin};
+
+#[derive(Debug, Parser)]
+struct Args {
+    first: MaybeStdin<String>,
+    second: FileOrStdin,
+}
+
+fn main() -> Result<(), String> {
+    Stdin::split_records("\n---\n");
+    let args = Args::parse();
+    println!(
+        "FIRST: {}; SECOND: {}",
+        args.first,
+        args.second.contents().map_err(|e| format!("{e}"))?
+    );
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use clap::Parser;
+
+use clap_stdin::{MaybeStdin, Stdin};
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[arg(long)]
+    name: MaybeStdin<String>,
+    #[arg(long)]
+    count: MaybeStdin<u32>,
+}
+
+fn main() {
+    Stdin::split_records("\n---\n");
+    let args = Args::parse();
+    println!("NAME: {}; COUNT: {}", args.name, args.count);
+}
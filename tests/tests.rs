@@ -1,9 +1,11 @@
+#![cfg(all(feature = "maybe-stdin", feature = "file"))]
+
 use std::fs;
 
 use assert_cmd::Command;
 use predicates::prelude::*;
 
-use clap_stdin::StdinError;
+use clap_stdin::{StdinError, MAX_BYTES_ENV, TIMEOUT_ENV};
 
 #[test]
 fn test_maybe_stdin_positional_arg() {
@@ -13,7 +15,7 @@ fn test_maybe_stdin_positional_arg() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "FIRST", second: Some("SECOND") }"#,
+            r#"Args { first: MaybeStdin { source: Literal, value: "FIRST" }, second: Some("SECOND") }"#,
         ));
     Command::cargo_bin("maybe_stdin_positional_arg")
         .unwrap()
@@ -22,7 +24,7 @@ fn test_maybe_stdin_positional_arg() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "TESTING", second: Some("SECOND") }"#,
+            r#"Args { first: MaybeStdin { source: Stdin, value: "TESTING" }, second: Some("SECOND") }"#,
         ));
     Command::cargo_bin("maybe_stdin_positional_arg")
         .unwrap()
@@ -31,7 +33,7 @@ fn test_maybe_stdin_positional_arg() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "FIRST", second: None }"#,
+            r#"Args { first: MaybeStdin { source: Literal, value: "FIRST" }, second: None }"#,
         ));
 }
 
@@ -43,7 +45,7 @@ fn test_maybe_stdin_optional_arg() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "FIRST", second: Some(2) }"#,
+            r#"Args { first: "FIRST", second: Some(MaybeStdin { source: Literal, value: 2 }) }"#,
         ));
     Command::cargo_bin("maybe_stdin_optional_arg")
         .unwrap()
@@ -52,7 +54,7 @@ fn test_maybe_stdin_optional_arg() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "FIRST", second: Some(2) }"#,
+            r#"Args { first: "FIRST", second: Some(MaybeStdin { source: Stdin, value: 2 }) }"#,
         ));
     Command::cargo_bin("maybe_stdin_optional_arg")
         .unwrap()
@@ -73,7 +75,7 @@ fn test_maybe_stdin_twice() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "FIRST", second: 2 }"#,
+            r#"Args { first: MaybeStdin { source: Literal, value: "FIRST" }, second: MaybeStdin { source: Literal, value: 2 } }"#,
         ));
     Command::cargo_bin("maybe_stdin_twice")
         .unwrap()
@@ -82,7 +84,7 @@ fn test_maybe_stdin_twice() {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            r#"Args { first: "FIRST", second: 2 }"#,
+            r#"Args { first: MaybeStdin { source: Literal, value: "FIRST" }, second: MaybeStdin { source: Stdin, value: 2 } }"#,
         ));
 
     // Actually using stdin twice will fail because there's no value the second time
@@ -167,20 +169,25 @@ fn test_file_or_stdin_twice() {
     let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
     fs::write(&tmp, "FILE").expect("couldn't write to temp file");
     let tmp_path = tmp.path().to_str().unwrap();
+    // The sync fixture prints the Debug output of the second arg, the async one its Display
+    let second = |source| match cfg!(feature = "tokio") {
+        true => "FIRST: FILE; SECOND: 2".to_owned(),
+        false => format!("FIRST: FILE; SECOND: MaybeStdin {{ source: {source}, value: 2 }}"),
+    };
 
     Command::cargo_bin("file_or_stdin_twice")
         .unwrap()
         .args([&tmp_path, "2"])
         .assert()
         .success()
-        .stdout(predicate::str::starts_with(r#"FIRST: FILE; SECOND: 2"#));
+        .stdout(predicate::str::starts_with(second("Literal")));
     Command::cargo_bin("file_or_stdin_twice")
         .unwrap()
         .write_stdin("2")
         .args([&tmp_path, "-"])
         .assert()
         .success()
-        .stdout(predicate::str::starts_with(r#"FIRST: FILE; SECOND: 2"#));
+        .stdout(predicate::str::starts_with(second("Stdin")));
 
     // Actually using stdin twice will fail because there's no value the second time
     Command::cargo_bin("file_or_stdin_twice")
@@ -227,3 +234,317 @@ fn test_is_stdin() {
             r#"FIRST is_stdin: true; SECOND is_stdin: false"#,
         ));
 }
+
+#[test]
+fn test_stdin_closed() {
+    Command::cargo_bin("maybe_stdin_positional_arg")
+        .unwrap()
+        .args(["-"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::StdinClosed.to_string(),
+        ));
+    Command::cargo_bin("file_or_stdin_positional_arg")
+        .unwrap()
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::StdinClosed.to_string(),
+        ));
+}
+
+#[test]
+fn test_max_bytes_env() {
+    Command::cargo_bin("maybe_stdin_positional_arg")
+        .unwrap()
+        .env(MAX_BYTES_ENV, "4")
+        .args(["-"])
+        .write_stdin("TESTING")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::InputTooLarge(4).to_string(),
+        ));
+    Command::cargo_bin("file_or_stdin_positional_arg")
+        .unwrap()
+        .env(MAX_BYTES_ENV, "4")
+        .write_stdin("TESTING")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::InputTooLarge(4).to_string(),
+        ));
+    // An invalid limit isn't ignored
+    Command::cargo_bin("file_or_stdin_positional_arg")
+        .unwrap()
+        .env(MAX_BYTES_ENV, "10MB")
+        .write_stdin("TESTING")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "environment variable {MAX_BYTES_ENV} is not a valid number"
+        )));
+}
+
+#[test]
+fn test_timeout_env() {
+    // Keep the pipe open without writing to it, like a forgotten producer
+    let mut child =
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("maybe_stdin_positional_arg"))
+            .env(TIMEOUT_ENV, "1")
+            .arg("-")
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+    let _stdin = child.stdin.take();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    let timeout = StdinError::Timeout(std::time::Duration::from_secs(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&timeout.to_string()));
+
+    Command::cargo_bin("maybe_stdin_positional_arg")
+        .unwrap()
+        .env(TIMEOUT_ENV, "1")
+        .args(["-"])
+        .write_stdin("TESTING")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_maybe_stdin_or_piped() {
+    Command::cargo_bin("maybe_stdin_or_piped")
+        .unwrap()
+        .args(["1"])
+        .write_stdin("2")
+        .assert()
+        .success()
+        .stdout("Some(1)\n");
+    Command::cargo_bin("maybe_stdin_or_piped")
+        .unwrap()
+        .write_stdin("2\n")
+        .assert()
+        .success()
+        .stdout("Some(2)\n");
+    // Closed without any input
+    Command::cargo_bin("maybe_stdin_or_piped")
+        .unwrap()
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout("None\n");
+
+    // Never closed, nor written to: the timeout is reported instead of skipping the pipe
+    let mut child =
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("maybe_stdin_or_piped"))
+            .env(TIMEOUT_ENV, "1")
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+    let _stdin = child.stdin.take();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Timeout(1s)"));
+}
+
+#[test]
+fn test_stdin_cached() {
+    Command::cargo_bin("stdin_cached")
+        .unwrap()
+        .args(["-", "-"])
+        .write_stdin("TESTING")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "FIRST: TESTING; SECOND: TESTING\nFIRST: SESSION; SECOND: SESSION",
+        ));
+
+    Command::cargo_bin("stdin_cached")
+        .unwrap()
+        .args(["-", "-"])
+        .env(MAX_BYTES_ENV, "4")
+        .write_stdin("TESTING")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("4 bytes"));
+}
+
+#[test]
+fn test_stdin_split_records() {
+    Command::cargo_bin("stdin_split_records")
+        .unwrap()
+        .args(["-", "-"])
+        .write_stdin("query {}\n---\n{\"id\": 1}\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"FIRST: query {}; SECOND: {"id": 1}"#,
+        ));
+    Command::cargo_bin("stdin_split_records")
+        .unwrap()
+        .env(MAX_BYTES_ENV, "4")
+        .args(["-", "-"])
+        .write_stdin("query {}\n---\n{\"id\": 1}\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::InputTooLarge(4).to_string(),
+        ));
+}
+
+#[test]
+fn test_stdin_split_records_order() {
+    // Records go to the args in the order they read stdin, which is the command line order
+    Command::cargo_bin("stdin_split_records_order")
+        .unwrap()
+        .args(["--count", "-", "--name", "-"])
+        .write_stdin("3\n---\nalice\n")
+        .assert()
+        .success()
+        .stdout("NAME: alice; COUNT: 3\n");
+    Command::cargo_bin("stdin_split_records_order")
+        .unwrap()
+        .args(["--name", "-", "--count", "-"])
+        .write_stdin("alice\n---\n3\n")
+        .assert()
+        .success()
+        .stdout("NAME: alice; COUNT: 3\n");
+}
+
+#[test]
+fn test_stdin_read_line_once() {
+    Command::cargo_bin("stdin_read_line_once")
+        .unwrap()
+        .write_stdin("TOKEN\r\nPAYLOAD\nMORE")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"TOKEN: Some("TOKEN"); PAYLOAD: "PAYLOAD\nMORE""#,
+        ));
+    Command::cargo_bin("stdin_read_line_once")
+        .unwrap()
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"TOKEN: None; PAYLOAD: """#));
+    Command::cargo_bin("stdin_read_line_once")
+        .unwrap()
+        .env(MAX_BYTES_ENV, "4")
+        .write_stdin("TOKEN\nPAYLOAD\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            StdinError::InputTooLarge(4).to_string(),
+        ));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_stdin_provenance() {
+    use clap_stdin::testing::{PROVENANCE_ENV, PROVENANCE_PREFIX};
+    use clap_stdin::{FileOrStdin, MaybeStdin};
+
+    let tmp = tempfile::NamedTempFile::new().expect("couldn't create temp file");
+    fs::write(&tmp, "FILE").expect("couldn't write to temp file");
+    let tmp_path = tmp.path().to_str().unwrap();
+
+    Command::cargo_bin("is_stdin")
+        .unwrap()
+        .env(PROVENANCE_ENV, "1")
+        .write_stdin("2")
+        .args([tmp_path, "-"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "{PROVENANCE_PREFIX}{}",
+            std::any::type_name::<MaybeStdin<u32>>()
+        )));
+    Command::cargo_bin("file_or_stdin_twice")
+        .unwrap()
+        .env(PROVENANCE_ENV, "1")
+        .write_stdin("FILE")
+        .args(["-", "2"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "{PROVENANCE_PREFIX}{}",
+            std::any::type_name::<FileOrStdin>()
+        )));
+    Command::cargo_bin("is_stdin")
+        .unwrap()
+        .env(PROVENANCE_ENV, "1")
+        .args([tmp_path, "2"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(PROVENANCE_PREFIX).not());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_command_stdin_ext() {
+    use clap_stdin::testing::CommandStdinExt;
+    use clap_stdin::MaybeStdin;
+
+    let provenance = Command::cargo_bin("maybe_stdin_twice")
+        .unwrap()
+        .args(["FIRST", "-"])
+        .feed_stdin("2")
+        .stdin_provenance()
+        .unwrap();
+    assert!(provenance.output().status.success());
+    assert!(provenance.consumed_by::<MaybeStdin<u32>>());
+    assert!(!provenance.consumed_by::<MaybeStdin<String>>());
+    assert_eq!(provenance.consumers().len(), 1);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_from_source() {
+    use clap_stdin::{FromSource, MaybeStdinFromSource, StdinSession};
+
+    #[derive(Debug, Clone, FromSource)]
+    #[from_source(stdin = "lines", arg = "split(',')")]
+    struct Ports(Vec<u16>);
+
+    #[derive(Debug, Clone, FromSource)]
+    struct Name {
+        value: String,
+    }
+
+    let ports: MaybeStdinFromSource<Ports> = "80, 443".parse().unwrap();
+    assert_eq!(ports.into_inner().0, [80, 443]);
+
+    let _session = StdinSession::with_input("22\n\n8080\n");
+    let ports: MaybeStdinFromSource<Ports> = "-".parse().unwrap();
+    assert!(ports.is_stdin());
+    assert_eq!(ports.into_inner().0, [22, 8080]);
+
+    let _session = StdinSession::with_input("22\nssh\n");
+    let error = "-".parse::<MaybeStdinFromSource<Ports>>().unwrap_err();
+    assert!(error.to_string().contains("line 2:"));
+
+    let _session = StdinSession::with_input(" from stdin \n");
+    let name: MaybeStdinFromSource<Name> = "-".parse().unwrap();
+    assert_eq!(name.into_inner().value, "from stdin");
+}
+
+#[cfg(all(feature = "derive", feature = "serde"))]
+#[test]
+fn test_derive_from_source_serde() {
+    use clap_stdin::{FromSource, MaybeStdinFromSource, StdinSession};
+
+    #[derive(Debug, Clone, serde::Deserialize, FromSource)]
+    #[from_source(serde = "json")]
+    struct Config {
+        retries: u32,
+    }
+
+    let config: MaybeStdinFromSource<Config> = r#"{"retries": 3}"#.parse().unwrap();
+    assert_eq!(config.into_inner().retries, 3);
+
+    let _session = StdinSession::with_input(r#"{"retries": 5}"#);
+    let config: MaybeStdinFromSource<Config> = "-".parse().unwrap();
+    assert_eq!(config.into_inner().retries, 5);
+}